@@ -3,6 +3,13 @@
 
 import builtins
 
+__all__ = [
+    "A",
+    "B",
+    "create_a",
+    "create_b",
+]
+
 class A:
     def show_x(self) -> None: ...
 
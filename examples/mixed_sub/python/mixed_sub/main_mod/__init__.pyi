@@ -2,9 +2,19 @@
 # ruff: noqa: E501, F401
 
 import builtins
-from . import int
-from . import mod_a
-from . import mod_b
+
+from . import int, mod_a, mod_b
+
+__all__ = [
+    "A",
+    "B",
+    "create_a",
+    "create_b",
+    "greet_main",
+    "int",
+    "mod_a",
+    "mod_b",
+]
 
 class A:
     def show_x(self) -> None: ...
@@ -3,6 +3,12 @@
 
 from mixed_sub.main_mod import A, B
 
+__all__ = [
+    "C",
+    "create_c",
+    "greet_a",
+]
+
 class C:
     def show_x(self) -> None: ...
 
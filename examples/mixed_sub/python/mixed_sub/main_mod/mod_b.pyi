@@ -3,6 +3,12 @@
 
 import builtins
 
+__all__ = [
+    "D",
+    "create_d",
+    "greet_b",
+]
+
 class D:
     def show_x(self) -> None: ...
 
@@ -3,6 +3,14 @@ use pyo3_stub_gen::Result;
 fn main() -> Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().filter_or("RUST_LOG", "info")).init();
     let stub = mixed_sub::stub_info()?;
-    stub.generate()?;
+    if std::env::args().any(|arg| arg == "--check") {
+        let report = stub.check()?;
+        if !report.is_up_to_date() {
+            eprint!("{report}");
+            std::process::exit(1);
+        }
+    } else {
+        stub.generate()?;
+    }
     Ok(())
 }
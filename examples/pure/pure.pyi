@@ -1,7 +1,7 @@
 # This file is automatically generated by pyo3_stub_gen
 # ruff: noqa: E501, F401
 r"""
-Document for pure v0.14.0 ...
+Document for pure v0.15.0 ...
 """
 
 import builtins
@@ -11,11 +11,75 @@ import decimal
 import os
 import pathlib
 import typing
-import typing_extensions
 from enum import Enum
 
+import typing_extensions
+
+__all__ = [
+    "A",
+    "B",
+    "ComparableStruct",
+    "DecimalHolder",
+    "HashableStruct",
+    "Incrementer",
+    "Incrementer2",
+    "MY_CONSTANT1",
+    "MY_CONSTANT2",
+    "MyDate",
+    "MyError",
+    "NotIntError",
+    "Number",
+    "NumberComplex",
+    "NumberRenameAll",
+    "OverrideType",
+    "Reader",
+    "Real",
+    "Shape1",
+    "Shape2",
+    "SolverOptions",
+    "Store",
+    "TypeIgnoreTest",
+    "add_decimals",
+    "ahash_dict",
+    "area",
+    "async_num",
+    "create_a",
+    "create_dict",
+    "default_value",
+    "deprecated_function",
+    "echo_path",
+    "fn_callback",
+    "fn_override_type",
+    "func_with_kwargs",
+    "func_with_star_arg",
+    "iterations",
+    "name_index",
+    "overload_example_1",
+    "overload_example_2",
+    "print_c",
+    "read_dict",
+    "read_header",
+    "solve",
+    "str_len",
+    "sum",
+    "test_type_ignore_all",
+    "test_type_ignore_custom",
+    "test_type_ignore_no_comment_all",
+    "test_type_ignore_no_comment_specific",
+    "test_type_ignore_pyright",
+    "test_type_ignore_specific",
+    "to_float",
+]
+
+T = typing.TypeVar("T")
+
 MY_CONSTANT1: builtins.int
 MY_CONSTANT2: builtins.int = 123
+Real: typing.TypeAlias = builtins.int | builtins.float
+r"""
+Integer or float accepted by `to_float`
+"""
+
 class A:
     NUM: builtins.int = 2
     r"""
@@ -63,7 +127,7 @@ class A:
     @classmethod
     def classmethod_test2(cls) -> None: ...
     def show_x(self) -> None: ...
-    def ref_test(self, x:dict) -> dict: ...
+    def ref_test(self, x:builtins.dict) -> builtins.dict: ...
     async def async_get_x(self) -> builtins.int: ...
     @typing_extensions.deprecated("[Since 1.0.0] This method is deprecated")
     def deprecated_method(self) -> None: ...
@@ -188,6 +252,15 @@ class OverrideType:
     def num(self, value: str) -> None: ...
     def error(self) -> typing_extensions.Never: ...
 
+class Reader(typing.Protocol):
+    r"""
+    Binary stream with `read`, e.g. `io.BytesIO` or a file opened by `open(path, "rb")`
+    """
+    def read(self, n:builtins.int) -> builtins.bytes:
+        r"""
+        Read at most `n` bytes
+        """
+
 class Shape1:
     r"""
     Example from PyO3 documentation for complex enum
@@ -256,6 +329,14 @@ class Shape2:
     
     ...
 
+class Store(typing.Generic[T]):
+    r"""
+    Container of Python objects, which can be annotated as `Store[int]`
+    """
+    def __new__(cls) -> Store: ...
+    def push(self, item:T) -> None: ...
+    def get(self, index:builtins.int) -> T: ...
+
 class TypeIgnoreTest:
     r"""
     Test class for method type: ignore functionality
@@ -292,6 +373,20 @@ class NumberRenameAll(Enum):
     """
     INTEGER = ...
 
+class SolverOptions(typing.TypedDict):
+    r"""
+    Options of `solve` given as a dict
+    """
+    max_iter: builtins.int
+    r"""
+    Maximum number of iterations
+    """
+    tolerance: builtins.float | None
+    r"""
+    `None` for the default tolerance
+    """
+    scale: typing_extensions.NotRequired[builtins.float | None]
+
 def add_decimals(a:decimal.Decimal, b:decimal.Decimal) -> decimal.Decimal:
     r"""
     Add two decimal numbers with high precision
@@ -299,6 +394,8 @@ def add_decimals(a:decimal.Decimal, b:decimal.Decimal) -> decimal.Decimal:
 
 def ahash_dict() -> builtins.dict[builtins.str, builtins.int]: ...
 
+def area(size:builtins.float | builtins.tuple[builtins.float, builtins.float] | typing.Mapping[builtins.str, typing.Any]) -> builtins.float: ...
+
 async def async_num() -> builtins.int: ...
 
 def create_a(x:builtins.int=2) -> A: ...
@@ -312,6 +409,11 @@ def deprecated_function() -> None: ...
 
 def echo_path(path:builtins.str | os.PathLike | pathlib.Path) -> pathlib.Path: ...
 
+def fn_callback(cb:collections.abc.Callable[[builtins.str], None]) -> collections.abc.Callable[[builtins.str], None]:
+    r"""
+    Same as `fn_override_type`, but typed by `PyCallable` instead of the handwritten annotations
+    """
+
 def fn_override_type(cb:collections.abc.Callable[[str]]) -> collections.abc.Callable[[str]]: ...
 
 def func_with_kwargs(**kwargs) -> builtins.bool:
@@ -324,6 +426,18 @@ def func_with_star_arg(*args) -> builtins.str:
     Takes a variable number of arguments and returns their string representation.
     """
 
+def internal_counter() -> builtins.int:
+    r"""
+    Helper exposed for the tests, but not a part of the public API
+    """
+
+def iterations(precision:typing.Literal["fast", "accurate"]) -> builtins.int: ...
+
+def name_index(names:builtins.list[builtins.str]) -> builtins.dict[builtins.str, builtins.int]:
+    r"""
+    Index of each name, created as a native `dict` from a native `list`
+    """
+
 @typing.overload
 def overload_example_1(x:builtins.int) -> builtins.int: ...
 
@@ -346,10 +460,17 @@ def overload_example_2(x:builtins.float) -> builtins.float:
     Increments float by 1
     """
 
-def print_c(c:typing.Optional[builtins.int]=None) -> None: ...
+def print_c(c:builtins.int | None=None) -> None: ...
 
 def read_dict(dict:typing.Mapping[builtins.int, typing.Mapping[builtins.int, builtins.int]]) -> None: ...
 
+def read_header(reader:Reader, size:builtins.int) -> builtins.bytes:
+    r"""
+    Read the first `size` bytes of the stream
+    """
+
+def solve(options:SolverOptions) -> builtins.float: ...
+
 def str_len(x:builtins.str) -> builtins.int:
     r"""
     Returns the length of the string.
@@ -384,3 +505,5 @@ def test_type_ignore_specific() -> builtins.int:  # type: ignore[arg-type,return
     Test function with type: ignore for specific rules
     """
 
+def to_float(x:Real) -> builtins.float: ...
+
@@ -7,14 +7,21 @@ use custom_exceptions::*;
 mod readme {}
 
 use ahash::RandomState;
-use pyo3::{exceptions::PyTypeError, prelude::*, types::*, IntoPyObjectExt, PyObject};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    pybacked::PyBackedBytes,
+    types::*,
+    IntoPyObjectExt, PyObject,
+};
 use pyo3_stub_gen::{
     define_stub_info_gatherer,
     derive::*,
     generate::MethodType,
     inventory::submit,
-    module_doc, module_variable,
+    module_doc, module_typevar, module_variable,
     type_info::{ArgInfo, MethodInfo, PyFunctionInfo, PyMethodsInfo},
+    typed::{PyCallable, PyDictOf, PyListOf, PyProtocol},
     PyStubType,
 };
 use rust_decimal::Decimal;
@@ -47,6 +54,14 @@ fn create_dict(n: usize) -> HashMap<usize, Vec<usize>> {
     dict
 }
 
+/// Helper exposed for the tests, but not a part of the public API
+#[gen_stub_pyfunction]
+#[gen_stub(private)]
+#[pyfunction]
+fn internal_counter() -> usize {
+    42
+}
+
 /// Add two decimal numbers with high precision
 #[gen_stub_pyfunction]
 #[pyfunction]
@@ -189,6 +204,90 @@ impl pyo3_stub_gen::PyStubType for C {
     }
 }
 
+/// Precision given as a string, `"fast"` or `"accurate"`
+#[gen_stub_literal(rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy)]
+enum Precision {
+    Fast,
+    Accurate,
+}
+impl FromPyObject<'_> for Precision {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        match ob.extract::<String>()?.as_str() {
+            "fast" => Ok(Precision::Fast),
+            "accurate" => Ok(Precision::Accurate),
+            other => Err(PyValueError::new_err(format!("Unknown precision: {other}"))),
+        }
+    }
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn iterations(precision: Precision) -> usize {
+    match precision {
+        Precision::Fast => 10,
+        Precision::Accurate => 1000,
+    }
+}
+
+/// Options of `solve` given as a dict
+#[gen_stub_typeddict]
+#[derive(FromPyObject)]
+#[pyo3(from_item_all)]
+struct SolverOptions {
+    /// Maximum number of iterations
+    max_iter: usize,
+    /// `None` for the default tolerance
+    tolerance: Option<f64>,
+    #[pyo3(default)]
+    scale: Option<f64>,
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn solve(options: SolverOptions) -> f64 {
+    options.tolerance.unwrap_or(1e-6) * options.max_iter as f64 * options.scale.unwrap_or(1.0)
+}
+
+/// Integer or float accepted by `to_float`
+#[gen_stub_union(alias = "Real")]
+#[derive(FromPyObject)]
+enum RealNumber {
+    Int(i64),
+    Float(f64),
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn to_float(x: RealNumber) -> f64 {
+    match x {
+        RealNumber::Int(i) => i as f64,
+        RealNumber::Float(f) => f,
+    }
+}
+
+#[gen_stub_union]
+#[derive(FromPyObject)]
+enum Size {
+    Square(f64),
+    Rect(f64, f64),
+    Dict {
+        #[pyo3(item)]
+        width: f64,
+        #[pyo3(item)]
+        height: f64,
+    },
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn area(size: Size) -> f64 {
+    match size {
+        Size::Square(side) => side * side,
+        Size::Rect(width, height) | Size::Dict { width, height } => width * height,
+    }
+}
+
 /// Returns the length of the string.
 #[gen_stub_pyfunction]
 #[pyfunction]
@@ -315,6 +414,65 @@ impl DecimalHolder {
 module_variable!("pure", "MY_CONSTANT1", usize);
 module_variable!("pure", "MY_CONSTANT2", usize, 123);
 
+module_typevar!("pure", "T");
+
+/// Container of Python objects, which can be annotated as `Store[int]`
+#[gen_stub_pyclass]
+#[pyclass(generic)]
+#[gen_stub(generic = (T,))]
+struct Store {
+    items: Vec<PyObject>,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Store {
+    #[new]
+    fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    fn push(&mut self, #[gen_stub(override_type(type_repr = "T"))] item: PyObject) {
+        self.items.push(item);
+    }
+
+    #[gen_stub(override_return_type(type_repr = "T"))]
+    fn get(&self, py: Python<'_>, index: usize) -> PyResult<PyObject> {
+        self.items
+            .get(index)
+            .map(|item| item.clone_ref(py))
+            .ok_or_else(|| pyo3::exceptions::PyIndexError::new_err(index))
+    }
+}
+
+/// Index of each name, created as a native `dict` from a native `list`
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn name_index<'py>(
+    py: Python<'py>,
+    names: PyListOf<'py, String>,
+) -> PyResult<PyDictOf<'py, String, usize>> {
+    let index = PyDict::new(py);
+    for (i, name) in names.iter().enumerate() {
+        index.set_item(name, i)?;
+    }
+    Ok(index.into())
+}
+
+/// Binary stream with `read`, e.g. `io.BytesIO` or a file opened by `open(path, "rb")`
+#[gen_stub_protocol(module = "pure")]
+pub trait Reader {
+    /// Read at most `n` bytes
+    fn read(&self, n: usize) -> PyBackedBytes;
+}
+
+/// Read the first `size` bytes of the stream
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn read_header(reader: PyProtocol<'_, dyn Reader>, size: usize) -> PyResult<PyBackedBytes> {
+    reader.call_method1("read", (size,))?.extract()
+}
+
 #[gen_stub_pyfunction]
 #[pyfunction]
 async fn async_num() -> i32 {
@@ -346,6 +504,17 @@ fn fn_override_type<'a>(
     cb.call1(("Hello!",))?;
     Ok(cb)
 }
+
+/// Same as `fn_override_type`, but typed by `PyCallable` instead of the handwritten annotations
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn fn_callback<'py>(
+    cb: PyCallable<'py, (String,), ()>,
+) -> PyResult<PyCallable<'py, (String,), ()>> {
+    cb.call1(("Hello!",))?;
+    Ok(cb)
+}
+
 #[gen_stub_pyclass]
 #[pyclass]
 struct OverrideType {
@@ -379,14 +548,9 @@ impl OverrideType {
 }
 
 // Test for `@overload` decorator generation
-
-/// First example: One generated with ordinary `#[gen_stub_pyfunction]`,
-/// and then manually with `submit!` macro.
-#[gen_stub_pyfunction]
-#[pyfunction]
-fn overload_example_1(x: f64) -> f64 {
-    x + 1.0
-}
+//
+// The overloads are written in the stub file in the order of the source,
+// so the more specific `int` ones are placed before the `float` ones.
 
 submit! {
     PyFunctionInfo {
@@ -402,35 +566,34 @@ submit! {
         is_async: false,
         deprecated: None,
         type_ignored: None,
+        location: std::panic::Location::caller(),
     }
 }
-/// Second example: all hints manually `submit!`ed via macro.
+
+/// First example: One generated with ordinary `#[gen_stub_pyfunction]`,
+/// and then manually with `submit!` macro.
+#[gen_stub_pyfunction]
 #[pyfunction]
-fn overload_example_2(ob: Bound<PyAny>) -> PyResult<PyObject> {
-    let py = ob.py();
-    if let Ok(f) = ob.extract::<f64>() {
-        (f + 1.0).into_py_any(py)
-    } else if let Ok(i) = ob.extract::<i64>() {
-        (i + 1).into_py_any(py)
-    } else {
-        Err(PyTypeError::new_err("Invalid type, expected float or int"))
-    }
+fn overload_example_1(x: f64) -> f64 {
+    x + 1.0
 }
 
+// Second example: all hints manually `submit!`ed via macro.
 submit! {
     PyFunctionInfo {
         name: "overload_example_2",
         args: &[ArgInfo{
             name: "x",
             signature: None,
-            r#type: || f64::type_input(),
+            r#type: || i64::type_input(),
         }],
-        r#return: || f64::type_output(),
+        r#return: || i64::type_output(),
         module: None,
-        doc: "Increments float by 1",
+        doc: "Increments integer by 1",
         is_async: false,
         deprecated: None,
         type_ignored: None,
+        location: std::panic::Location::caller(),
     }
 }
 
@@ -440,14 +603,27 @@ submit! {
         args: &[ArgInfo{
             name: "x",
             signature: None,
-            r#type: || i64::type_input(),
+            r#type: || f64::type_input(),
         }],
-        r#return: || i64::type_output(),
+        r#return: || f64::type_output(),
         module: None,
-        doc: "Increments integer by 1",
+        doc: "Increments float by 1",
         is_async: false,
         deprecated: None,
         type_ignored: None,
+        location: std::panic::Location::caller(),
+    }
+}
+
+#[pyfunction]
+fn overload_example_2(ob: Bound<PyAny>) -> PyResult<PyObject> {
+    let py = ob.py();
+    if let Ok(f) = ob.extract::<f64>() {
+        (f + 1.0).into_py_any(py)
+    } else if let Ok(i) = ob.extract::<i64>() {
+        (i + 1).into_py_any(py)
+    } else {
+        Err(PyTypeError::new_err("Invalid type, expected float or int"))
     }
 }
 
@@ -456,23 +632,10 @@ submit! {
 #[gen_stub_pyclass]
 pub struct Incrementer {}
 
-#[pymethods]
-#[gen_stub_pymethods]
-impl Incrementer {
-    #[new]
-    fn new() -> Self {
-        Incrementer {}
-    }
-
-    /// This is the original doc comment
-    fn increment_1(&self, x: f64) -> f64 {
-        x + 1.0
-    }
-}
-
 submit! {
     PyMethodsInfo {
         struct_id: std::any::TypeId::of::<Incrementer>,
+        struct_name: std::any::type_name::<Incrementer>,
         attrs: &[],
         getters: &[],
         setters: &[],
@@ -494,6 +657,21 @@ submit! {
                 type_ignored: None,
             }
         ],
+        location: std::panic::Location::caller(),
+    }
+}
+
+#[pymethods]
+#[gen_stub_pymethods]
+impl Incrementer {
+    #[new]
+    fn new() -> Self {
+        Incrementer {}
+    }
+
+    /// This is the original doc comment
+    fn increment_1(&self, x: f64) -> f64 {
+        x + 1.0
     }
 }
 
@@ -518,6 +696,7 @@ impl Incrementer2 {
 submit! {
     PyMethodsInfo {
         struct_id: std::any::TypeId::of::<Incrementer2>,
+        struct_name: std::any::type_name::<Incrementer2>,
         attrs: &[],
         getters: &[],
         setters: &[],
@@ -565,6 +744,7 @@ submit! {
                 type_ignored: None,
             },
         ],
+        location: std::panic::Location::caller(),
     }
 }
 
@@ -653,6 +833,7 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<ComparableStruct>()?;
     m.add_class::<HashableStruct>()?;
     m.add_class::<DecimalHolder>()?;
+    m.add_class::<Store>()?;
     m.add_function(wrap_pyfunction!(sum, m)?)?;
     m.add_function(wrap_pyfunction!(create_dict, m)?)?;
     m.add_function(wrap_pyfunction!(read_dict, m)?)?;
@@ -665,9 +846,17 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(deprecated_function, m)?)?;
     m.add_function(wrap_pyfunction!(default_value, m)?)?;
     m.add_function(wrap_pyfunction!(fn_override_type, m)?)?;
+    m.add_function(wrap_pyfunction!(fn_callback, m)?)?;
     m.add_function(wrap_pyfunction!(overload_example_1, m)?)?;
     m.add_function(wrap_pyfunction!(overload_example_2, m)?)?;
     m.add_function(wrap_pyfunction!(add_decimals, m)?)?;
+    m.add_function(wrap_pyfunction!(internal_counter, m)?)?;
+    m.add_function(wrap_pyfunction!(iterations, m)?)?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(to_float, m)?)?;
+    m.add_function(wrap_pyfunction!(area, m)?)?;
+    m.add_function(wrap_pyfunction!(read_header, m)?)?;
+    m.add_function(wrap_pyfunction!(name_index, m)?)?;
     // Test-cases for `*args` and `**kwargs`
     m.add_function(wrap_pyfunction!(func_with_star_arg, m)?)?;
     m.add_function(wrap_pyfunction!(func_with_kwargs, m)?)?;
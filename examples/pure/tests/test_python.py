@@ -13,7 +13,16 @@ from pure import (
     HashableStruct,
     add_decimals,
     DecimalHolder,
+    iterations,
+    solve,
+    to_float,
+    area,
+    Store,
+    read_header,
+    fn_callback,
+    name_index,
 )
+import io
 import pytest
 import pathlib
 
@@ -231,3 +240,61 @@ def test_decimal_holder():
     # Test creating a DecimalHolder
     holder = DecimalHolder(Decimal("123.45"))
     assert holder.value == Decimal("123.45")
+
+
+def test_iterations():
+    """Test the `typing.Literal` argument is extracted from a string"""
+    assert iterations("fast") == 10
+    assert iterations("accurate") == 1000
+    with pytest.raises(ValueError):
+        iterations("slow")  # type: ignore
+
+
+def test_solve():
+    """Test the `typing.TypedDict` argument is extracted from a dict"""
+    assert solve({"max_iter": 10, "tolerance": 0.5}) == 5.0
+    assert solve({"max_iter": 10, "tolerance": None, "scale": 2.0}) == pytest.approx(2e-5)
+    with pytest.raises(KeyError):
+        solve({"tolerance": 0.5})  # type: ignore
+    # `Option<T>` without `#[pyo3(default)]` is still required
+    with pytest.raises(KeyError):
+        solve({"max_iter": 10})  # type: ignore
+
+
+def test_union():
+    """Test the union of the variants of `#[derive(FromPyObject)]` enum"""
+    assert to_float(1) == 1.0
+    assert to_float(1.5) == 1.5
+    assert area(2.0) == 4.0
+    assert area((2.0, 3.0)) == 6.0
+    assert area({"width": 2.0, "height": 3.0}) == 6.0
+
+
+def test_generic_class():
+    """Test the `#[pyclass(generic)]` class can be subscripted"""
+    store: Store[int] = Store[int]()
+    store.push(1)
+    assert store.get(0) == 1
+    with pytest.raises(IndexError):
+        store.get(1)
+
+
+def test_protocol():
+    """Test an object implementing the `Reader` protocol is accepted"""
+    assert read_header(io.BytesIO(b"header and body"), 6) == b"header"
+
+
+def test_callback():
+    """Test `PyCallable` is called with a `str` and returned as is"""
+    received = []
+    cb = fn_callback(received.append)
+    assert received == ["Hello!"]
+    cb("again")
+    assert received == ["Hello!", "again"]
+
+
+def test_name_index():
+    """Test `PyListOf` accepts only a `list` and `PyDictOf` is returned as `dict`"""
+    assert name_index(["a", "b"]) == {"a": 0, "b": 1}
+    with pytest.raises(TypeError):
+        name_index(("a", "b"))  # type: ignore
@@ -2,6 +2,14 @@ use pyo3_stub_gen::Result;
 
 fn main() -> Result<()> {
     let stub = test_dash_package::stub_info()?;
-    stub.generate()?;
+    if std::env::args().any(|arg| arg == "--check") {
+        let report = stub.check()?;
+        if !report.is_up_to_date() {
+            eprint!("{report}");
+            std::process::exit(1);
+        }
+    } else {
+        stub.generate()?;
+    }
     Ok(())
 }
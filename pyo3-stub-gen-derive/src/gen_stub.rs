@@ -37,6 +37,7 @@
 //!         setters: &[],
 //!         doc: "",
 //!         bases: &[],
+//!         type_params: &[],
 //!         has_eq: false,
 //!         has_ord: false,
 //!         has_hash: false,
@@ -76,8 +77,11 @@
 
 mod arg;
 mod attr;
+mod literal;
 mod member;
 mod method;
+mod private;
+mod protocol;
 mod pyclass;
 mod pyclass_complex_enum;
 mod pyclass_enum;
@@ -86,13 +90,18 @@ mod pymethods;
 mod renaming;
 mod signature;
 mod stub_type;
+mod typed_dict;
+mod union;
 mod util;
 mod variant;
 
 use arg::*;
 use attr::*;
+use literal::*;
 use member::*;
 use method::*;
+use private::*;
+use protocol::*;
 use pyclass::*;
 use pyclass_complex_enum::*;
 use pyclass_enum::*;
@@ -101,16 +110,38 @@ use pymethods::*;
 use renaming::*;
 use signature::*;
 use stub_type::*;
+use typed_dict::*;
+use union::*;
 use util::*;
 
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse2, ItemEnum, ItemFn, ItemImpl, ItemStruct, Result};
+use syn::{parse2, Attribute, ItemEnum, ItemFn, ItemImpl, ItemStruct, ItemTrait, Result};
+
+/// `inventory::submit!` of [PrivateInfo] if the item has `#[gen_stub(private)]`
+fn submit_private(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+    info: impl FnOnce() -> PrivateInfo,
+) -> Result<Option<TokenStream2>> {
+    if !parse_gen_stub_private(attrs, location)? {
+        return Ok(None);
+    }
+    let info = info();
+    Ok(Some(quote! {
+        pyo3_stub_gen::inventory::submit! {
+            #info
+        }
+    }))
+}
 
 pub fn pyclass(item: TokenStream2) -> Result<TokenStream2> {
     let mut item_struct = parse2::<ItemStruct>(item)?;
     let inner = PyClassInfo::try_from(item_struct.clone())?;
     let derive_stub_type = StubType::from(&inner);
+    let private = submit_private(&item_struct.attrs, AttributeLocation::Item, || {
+        PrivateInfo::from(&derive_stub_type)
+    })?;
     pyclass::prune_attrs(&mut item_struct);
     Ok(quote! {
         #item_struct
@@ -118,26 +149,91 @@ pub fn pyclass(item: TokenStream2) -> Result<TokenStream2> {
         pyo3_stub_gen::inventory::submit! {
             #inner
         }
+        #private
     })
 }
 
 pub fn pyclass_enum(item: TokenStream2) -> Result<TokenStream2> {
-    let inner = PyEnumInfo::try_from(parse2::<ItemEnum>(item.clone())?)?;
+    let mut item_enum = parse2::<ItemEnum>(item)?;
+    let inner = PyEnumInfo::try_from(item_enum.clone())?;
     let derive_stub_type = StubType::from(&inner);
+    let private = submit_private(&item_enum.attrs, AttributeLocation::Item, || {
+        PrivateInfo::from(&derive_stub_type)
+    })?;
+    attr::prune_attrs(&mut item_enum.attrs);
     Ok(quote! {
-        #item
+        #item_enum
         #derive_stub_type
         pyo3_stub_gen::inventory::submit! {
             #inner
         }
+        #private
     })
 }
 
 pub fn pyclass_complex_enum(item: TokenStream2) -> Result<TokenStream2> {
-    let inner = PyComplexEnumInfo::try_from(parse2::<ItemEnum>(item.clone())?)?;
+    let mut item_enum = parse2::<ItemEnum>(item)?;
+    let inner = PyComplexEnumInfo::try_from(item_enum.clone())?;
+    let derive_stub_type = StubType::from(&inner);
+    let private = submit_private(&item_enum.attrs, AttributeLocation::Item, || {
+        PrivateInfo::from(&derive_stub_type)
+    })?;
+    attr::prune_attrs(&mut item_enum.attrs);
+    Ok(quote! {
+        #item_enum
+        #derive_stub_type
+        pyo3_stub_gen::inventory::submit! {
+            #inner
+        }
+        #private
+    })
+}
+
+pub fn typed_dict(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
+    let mut item_struct = parse2::<ItemStruct>(item)?;
+    let inner = PyTypedDictInfo::new(attr, &item_struct)?;
+    let derive_stub_type = StubType::from(&inner);
+    let private = submit_private(&item_struct.attrs, AttributeLocation::Item, || {
+        PrivateInfo::from(&derive_stub_type)
+    })?;
+    pyclass::prune_attrs(&mut item_struct);
+    Ok(quote! {
+        #item_struct
+        #derive_stub_type
+        pyo3_stub_gen::inventory::submit! {
+            #inner
+        }
+        #private
+    })
+}
+
+pub fn literal(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
+    let mut item_enum = parse2::<ItemEnum>(item)?;
+    let inner = LiteralInfo::new(attr, &item_enum)?;
+    literal::prune_attrs(&mut item_enum);
+    Ok(quote! {
+        #item_enum
+        #inner
+    })
+}
+
+pub fn union(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
+    let mut item_enum = parse2::<ItemEnum>(item)?;
+    let inner = UnionInfo::new(attr, &item_enum)?;
+    union::prune_attrs(&mut item_enum);
+    Ok(quote! {
+        #item_enum
+        #inner
+    })
+}
+
+pub fn protocol(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
+    let mut item_trait = parse2::<ItemTrait>(item)?;
+    let inner = PyProtocolInfo::new(attr, &item_trait)?;
     let derive_stub_type = StubType::from(&inner);
+    protocol::prune_attrs(&mut item_trait);
     Ok(quote! {
-        #item
+        #item_trait
         #derive_stub_type
         pyo3_stub_gen::inventory::submit! {
             #inner
@@ -162,6 +258,9 @@ pub fn pyfunction(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2
     let mut item_fn = parse2::<ItemFn>(item)?;
     let mut inner = PyFunctionInfo::try_from(item_fn.clone())?;
     inner.parse_attr(attr)?;
+    let private = submit_private(&item_fn.attrs, AttributeLocation::Function, || {
+        inner.private_info()
+    })?;
     pyfunction::prune_attrs(&mut item_fn);
     Ok(quote! {
         #item_fn
@@ -169,6 +268,7 @@ pub fn pyfunction(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2
         pyo3_stub_gen::inventory::submit! {
             #inner
         }
+        #private
     })
 }
 
@@ -185,4 +285,5 @@ pub fn prune_gen_stub(item: TokenStream2) -> Result<TokenStream2> {
     prune_attrs::<ItemStruct>(&item, pyclass::prune_attrs)
         .or_else(|_| prune_attrs::<ItemImpl>(&item, pymethods::prune_attrs))
         .or_else(|_| prune_attrs::<ItemFn>(&item, pyfunction::prune_attrs))
+        .or_else(|_| prune_attrs::<ItemEnum>(&item, literal::prune_attrs))
 }
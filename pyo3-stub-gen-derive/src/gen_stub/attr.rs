@@ -133,6 +133,16 @@ pub enum Attr {
     RenameAll(RenamingRule),
     Extends(Type),
 
+    // Attributes of `#[derive(FromPyObject)]`
+    /// `#[pyo3(item)]` or `#[pyo3(item("key"))]`
+    Item(Option<String>),
+    /// `#[pyo3(from_item_all)]`
+    FromItemAll,
+    /// `#[pyo3(default)]` or `#[pyo3(default = ...)]`
+    Default,
+    /// `#[pyo3(transparent)]`
+    Transparent,
+
     // Comparison and special method attributes for pyclass
     Eq,
     Ord,
@@ -191,6 +201,10 @@ pub fn parse_pyo3_attr(attr: &Attribute) -> Result<Vec<Attr>> {
                     false
                 }
             }) {
+                if matches!(tt.first(), Some(Ident(ident)) if ident == "default") {
+                    pyo3_attrs.push(Attr::Default);
+                    continue;
+                }
                 match tt {
                     [Ident(ident)] => {
                         if ident == "get" {
@@ -217,6 +231,15 @@ pub fn parse_pyo3_attr(attr: &Attribute) -> Result<Vec<Attr>> {
                         if ident == "str" {
                             pyo3_attrs.push(Attr::Str);
                         }
+                        if ident == "item" {
+                            pyo3_attrs.push(Attr::Item(None));
+                        }
+                        if ident == "from_item_all" {
+                            pyo3_attrs.push(Attr::FromItemAll);
+                        }
+                        if ident == "transparent" {
+                            pyo3_attrs.push(Attr::Transparent);
+                        }
                         // frozen is required by PyO3 when using hash, but doesn't affect stub generation
                     }
                     [Ident(ident), Punct(_), Literal(lit)] => {
@@ -243,10 +266,12 @@ pub fn parse_pyo3_attr(attr: &Attribute) -> Result<Vec<Attr>> {
                                 .push(Attr::Constructor(syn::parse2(group.to_token_stream())?));
                         }
                     }
-                    [Ident(ident), Punct(_), Ident(ident2)] => {
-                        if ident == "extends" {
-                            pyo3_attrs.push(Attr::Extends(syn::parse2(ident2.to_token_stream())?));
-                        }
+                    [Ident(ident), Group(group)] if ident == "item" => {
+                        let key: LitStr = syn::parse2(group.stream())?;
+                        pyo3_attrs.push(Attr::Item(Some(key.value())));
+                    }
+                    [Ident(ident), Punct(_), Ident(ident2)] if ident == "extends" => {
+                        pyo3_attrs.push(Attr::Extends(syn::parse2(ident2.to_token_stream())?));
                     }
                     _ => {}
                 }
@@ -287,6 +312,12 @@ pub enum StubGenAttr {
     OverrideType(OverrideTypeAttribute),
     /// Type checker rules to ignore for this function/method
     TypeIgnore(IgnoreTarget),
+    /// Exclude a class, enum, or function from `__all__`
+    Private,
+    /// Value of a variant of `#[gen_stub_literal]` enum
+    Literal(Expr),
+    /// Type variables of a generic class, i.e. `typing.Generic[T, ...]`
+    Generic(Vec<String>),
 }
 
 pub fn prune_attrs(attrs: &mut Vec<Attribute>) {
@@ -302,6 +333,17 @@ pub fn parse_gen_stub_override_type(attrs: &[Attribute]) -> Result<Option<Overri
     Ok(None)
 }
 
+pub fn parse_gen_stub_variant_override_type(
+    attrs: &[Attribute],
+) -> Result<Option<OverrideTypeAttribute>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Variant, None)? {
+        if let StubGenAttr::OverrideType(attr) = attr {
+            return Ok(Some(attr));
+        }
+    }
+    Ok(None)
+}
+
 pub fn parse_gen_stub_override_return_type(
     attrs: &[Attribute],
 ) -> Result<Option<OverrideTypeAttribute>> {
@@ -332,15 +374,29 @@ pub fn parse_gen_stub_skip(attrs: &[Attribute]) -> Result<bool> {
     Ok(skip)
 }
 
-pub fn parse_gen_stub_type_ignore(attrs: &[Attribute]) -> Result<Option<IgnoreTarget>> {
-    // Try Function location first (for regular functions)
-    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, None)? {
-        if let StubGenAttr::TypeIgnore(target) = attr {
-            return Ok(Some(target));
+pub fn parse_gen_stub_private(attrs: &[Attribute], location: AttributeLocation) -> Result<bool> {
+    let private = parse_gen_stub_attrs(attrs, location, None)?
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::Private));
+    Ok(private)
+}
+
+pub fn parse_gen_stub_generic(attrs: &[Attribute]) -> Result<Vec<String>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Item, None)? {
+        if let StubGenAttr::Generic(type_vars) = attr {
+            return Ok(type_vars);
         }
     }
-    // Try Field location (for methods in #[pymethods] blocks)
-    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Field, None)? {
+    Ok(Vec::new())
+}
+
+/// `location` is [AttributeLocation::Function] for `#[pyfunction]`,
+/// and [AttributeLocation::Field] for methods in `#[pymethods]` where e.g. `private` is rejected.
+pub fn parse_gen_stub_type_ignore(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<Option<IgnoreTarget>> {
+    for attr in parse_gen_stub_attrs(attrs, location, None)? {
         if let StubGenAttr::TypeIgnore(target) = attr {
             return Ok(Some(target));
         }
@@ -348,6 +404,15 @@ pub fn parse_gen_stub_type_ignore(attrs: &[Attribute]) -> Result<Option<IgnoreTa
     Ok(None)
 }
 
+pub fn parse_gen_stub_literal(attrs: &[Attribute]) -> Result<Option<Expr>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Variant, None)? {
+        if let StubGenAttr::Literal(value) = attr {
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
 fn parse_gen_stub_attrs(
     attrs: &[Attribute],
     location: AttributeLocation,
@@ -374,7 +439,7 @@ fn parse_gen_stub_attr(
                 let ident: Ident = input.parse()?;
                 let ignored_ident = ignored_idents.iter().any(|other| ident == other);
                 if (ident == "override_type"
-                    && (location == AttributeLocation::Argument || ignored_ident))
+                    && (location == AttributeLocation::Argument || location == AttributeLocation::Variant || ignored_ident))
                     || (ident == "override_return_type"
                         && (location == AttributeLocation::Function || location == AttributeLocation::Field || ignored_ident))
                 {
@@ -419,10 +484,31 @@ fn parse_gen_stub_attr(
                         // No equals sign means catch-all
                         gen_stub_attrs.push(StubGenAttr::TypeIgnore(IgnoreTarget::All));
                     }
+                } else if ident == "private"
+                    && (location == AttributeLocation::Function || location == AttributeLocation::Item || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::Private);
+                } else if ident == "generic"
+                    && input.peek(Token![=])
+                    && (location == AttributeLocation::Item || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    let content;
+                    parenthesized!(content in input);
+                    let type_vars = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                    gen_stub_attrs.push(StubGenAttr::Generic(
+                        type_vars.iter().map(Ident::to_string).collect(),
+                    ));
+                } else if ident == "literal"
+                    && input.peek(Token![=])
+                    && (location == AttributeLocation::Variant || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    gen_stub_attrs.push(StubGenAttr::Literal(input.parse()?));
                 } else if ident == "override_type" {
                     return Err(syn::Error::new(
                         ident.span(),
-                        "`override_type(...)` is only valid in argument position".to_string(),
+                        "`override_type(...)` is only valid in argument or variant position".to_string(),
                     ));
                 } else if ident == "override_return_type" {
                     return Err(syn::Error::new(
@@ -445,6 +531,21 @@ fn parse_gen_stub_attr(
                         ident.span(),
                         "`type_ignore` or `type_ignore=[...]` is only valid in function or method position".to_string(),
                     ));
+                } else if ident == "private" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`private` is only valid in class, enum, or function position".to_string(),
+                    ));
+                } else if ident == "generic" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`generic = (...)` is only valid in class position".to_string(),
+                    ));
+                } else if ident == "literal" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`literal=xxx` is only valid in variant position of #[gen_stub_literal]".to_string(),
+                    ));
                 } else if location == AttributeLocation::Argument {
                     return Err(syn::Error::new(
                         ident.span(),
@@ -459,9 +560,19 @@ fn parse_gen_stub_attr(
                     return Err(syn::Error::new(
                         ident.span(),
                         format!(
-                            "Unsupported keyword `{ident}`, valid is `default=xxx`, `override_return_type(...)`, `type_ignore`, or `type_ignore=[...]`"
+                            "Unsupported keyword `{ident}`, valid is `default=xxx`, `override_return_type(...)`, `type_ignore`, `type_ignore=[...]`, or `private`"
                         ),
                     ));
+                } else if location == AttributeLocation::Item {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("Unsupported keyword `{ident}`, valid is `private` or `generic = (...)`"),
+                    ));
+                } else if location == AttributeLocation::Variant {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("Unsupported keyword `{ident}`, valid is `literal=xxx` or `override_type(...)`"),
+                    ));
                 } else {
                     return Err(syn::Error::new(
                         ident.span(),
@@ -485,6 +596,10 @@ pub(crate) enum AttributeLocation {
     Argument,
     Field,
     Function,
+    /// `#[gen_stub_pyclass]`, `#[gen_stub_pyclass_enum]`, and `#[gen_stub_pyclass_complex_enum]`
+    Item,
+    /// Variant of `#[gen_stub_literal]` or `#[gen_stub_union]` enum
+    Variant,
 }
 
 #[derive(Debug, Clone, PartialEq)]
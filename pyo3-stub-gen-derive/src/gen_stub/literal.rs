@@ -0,0 +1,243 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Expr, ExprLit, ExprUnary, Ident, ItemEnum, Lit, LitStr, Result, Token, UnOp,
+};
+
+use super::{attr, parse_gen_stub_literal, RenamingRule};
+
+/// Fieldless Rust enum with `#[gen_stub_literal]`, whose stub type is `typing.Literal[...]` of the variants
+pub struct LiteralInfo {
+    ident: Ident,
+    values: Vec<LiteralValue>,
+}
+
+/// Value of a variant in `typing.Literal[...]`
+enum LiteralValue {
+    Str(String),
+    Int(i64),
+}
+
+impl LiteralValue {
+    fn from_lit(lit: &Lit, negative: bool) -> Result<Self> {
+        match lit {
+            Lit::Str(s) if !negative => Ok(Self::Str(s.value())),
+            Lit::Int(i) => {
+                let value: i64 = i.base10_parse()?;
+                Ok(Self::Int(if negative { -value } else { value }))
+            }
+            _ => Err(Error::new_spanned(
+                lit,
+                "Literal value must be a string or an integer",
+            )),
+        }
+    }
+
+    fn from_expr(expr: &Expr) -> Result<Self> {
+        match expr {
+            Expr::Lit(ExprLit { lit, .. }) => Self::from_lit(lit, false),
+            Expr::Unary(ExprUnary {
+                op: UnOp::Neg(_),
+                expr,
+                ..
+            }) => match expr.as_ref() {
+                Expr::Lit(ExprLit { lit, .. }) => Self::from_lit(lit, true),
+                _ => Err(Error::new_spanned(expr, "Expected an integer literal")),
+            },
+            _ => Err(Error::new_spanned(
+                expr,
+                "Literal value must be a string or an integer",
+            )),
+        }
+    }
+}
+
+impl ToTokens for LiteralValue {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        tokens.append_all(match self {
+            Self::Str(value) => quote! { ::pyo3_stub_gen::LiteralValue::Str(#value.to_string()) },
+            Self::Int(value) => quote! { ::pyo3_stub_gen::LiteralValue::Int(#value) },
+        })
+    }
+}
+
+/// Arguments of `#[gen_stub_literal(...)]`
+#[derive(Default)]
+struct LiteralAttr {
+    /// `rename_all = "..."` applied to the variant names
+    rename_all: Option<RenamingRule>,
+    /// `int`, use the discriminants instead of the variant names
+    int: bool,
+}
+
+impl Parse for LiteralAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attr = Self::default();
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            if ident == "rename_all" {
+                input.parse::<Token![=]>()?;
+                let rule: LitStr = input.parse()?;
+                attr.rename_all = Some(RenamingRule::try_new(&rule.value()).ok_or_else(|| {
+                    Error::new(
+                        rule.span(),
+                        format!("Unknown renaming rule `{}`", rule.value()),
+                    )
+                })?);
+            } else if ident == "int" {
+                attr.int = true;
+            } else {
+                return Err(Error::new(
+                    ident.span(),
+                    format!(
+                        "Unsupported keyword `{ident}`, valid is `rename_all = \"...\"` or `int`"
+                    ),
+                ));
+            }
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            } else {
+                break;
+            }
+        }
+        Ok(attr)
+    }
+}
+
+impl LiteralInfo {
+    pub fn new(attr: TokenStream2, item: &ItemEnum) -> Result<Self> {
+        let attr: LiteralAttr = syn::parse2(attr)?;
+        if attr.int && attr.rename_all.is_some() {
+            return Err(Error::new_spanned(
+                &item.ident,
+                "`rename_all` cannot be used with `int`",
+            ));
+        }
+        let mut values = Vec::new();
+        let mut next_discriminant = 0;
+        for variant in &item.variants {
+            if !variant.fields.is_empty() {
+                return Err(Error::new_spanned(
+                    variant,
+                    "#[gen_stub_literal] only supports fieldless enums",
+                ));
+            }
+            if let Some((_, discriminant)) = variant.discriminant.as_ref().filter(|_| attr.int) {
+                match LiteralValue::from_expr(discriminant)? {
+                    LiteralValue::Int(value) => next_discriminant = value,
+                    LiteralValue::Str(_) => {
+                        return Err(Error::new_spanned(discriminant, "Expected an integer"))
+                    }
+                }
+            }
+            let value = if let Some(value) = parse_gen_stub_literal(&variant.attrs)? {
+                LiteralValue::from_expr(&value)?
+            } else if attr.int {
+                LiteralValue::Int(next_discriminant)
+            } else {
+                let name = variant.ident.to_string();
+                LiteralValue::Str(match attr.rename_all {
+                    Some(rule) => rule.apply(&name),
+                    None => name,
+                })
+            };
+            values.push(value);
+            next_discriminant += 1;
+        }
+        Ok(Self {
+            ident: item.ident.clone(),
+            values,
+        })
+    }
+}
+
+impl ToTokens for LiteralInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self { ident, values } = self;
+        tokens.append_all(quote! {
+            #[automatically_derived]
+            impl ::pyo3_stub_gen::PyStubType for #ident {
+                fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                    ::pyo3_stub_gen::TypeInfo::literal([ #(#values),* ])
+                }
+            }
+        })
+    }
+}
+
+pub fn prune_attrs(item: &mut ItemEnum) {
+    attr::prune_attrs(&mut item.attrs);
+    for variant in &mut item.variants {
+        attr::prune_attrs(&mut variant.attrs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quote::quote;
+    use syn::parse_str;
+
+    fn format(tt: TokenStream2) -> String {
+        prettyplease::unparse(&syn::parse_file(&tt.to_string()).unwrap())
+    }
+
+    #[test]
+    fn test_literal() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            pub enum Mode {
+                Fast,
+                AccurateMode,
+                #[gen_stub(literal = "auto-detect")]
+                Auto,
+            }
+            "#,
+        )?;
+        let out = LiteralInfo::new(quote! { rename_all = "snake_case" }, &input)?.to_token_stream();
+        insta::assert_snapshot!(format(out), @r###"
+        #[automatically_derived]
+        impl ::pyo3_stub_gen::PyStubType for Mode {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::literal([
+                    ::pyo3_stub_gen::LiteralValue::Str("fast".to_string()),
+                    ::pyo3_stub_gen::LiteralValue::Str("accurate_mode".to_string()),
+                    ::pyo3_stub_gen::LiteralValue::Str("auto-detect".to_string()),
+                ])
+            }
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_literal_int() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            pub enum Level {
+                Low = -1,
+                Middle,
+                #[gen_stub(literal = 10)]
+                High = 5,
+                Higher,
+            }
+            "#,
+        )?;
+        let out = LiteralInfo::new(quote! { int }, &input)?.to_token_stream();
+        insta::assert_snapshot!(format(out), @r###"
+        #[automatically_derived]
+        impl ::pyo3_stub_gen::PyStubType for Level {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::literal([
+                    ::pyo3_stub_gen::LiteralValue::Int(-1i64),
+                    ::pyo3_stub_gen::LiteralValue::Int(0i64),
+                    ::pyo3_stub_gen::LiteralValue::Int(10i64),
+                    ::pyo3_stub_gen::LiteralValue::Int(6i64),
+                ])
+            }
+        }
+        "###);
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use crate::gen_stub::util::TypeOrOverride;
 use super::{
     arg::parse_args, attr::IgnoreTarget, extract_deprecated, extract_documents,
     extract_return_type, parse_gen_stub_type_ignore, parse_pyo3_attrs, ArgInfo, ArgsWithSignature,
-    Attr, DeprecatedInfo, Signature,
+    Attr, AttributeLocation, DeprecatedInfo, Signature,
 };
 
 use proc_macro2::TokenStream as TokenStream2;
@@ -91,7 +91,7 @@ impl TryFrom<ImplItemFn> for MethodInfo {
         let ImplItemFn { attrs, sig, .. } = item;
         let doc = extract_documents(&attrs).join("\n");
         let deprecated = extract_deprecated(&attrs);
-        let type_ignored = parse_gen_stub_type_ignore(&attrs)?;
+        let type_ignored = parse_gen_stub_type_ignore(&attrs, AttributeLocation::Field)?;
         let pyo3_attrs = parse_pyo3_attrs(&attrs)?;
         let mut method_name = None;
         let mut text_sig = Signature::overriding_operator(&sig);
@@ -151,7 +151,7 @@ impl ToTokens for MethodInfo {
                 } => {
                     let imports = imports.iter().collect::<Vec<&String>>();
                     quote! {
-                        || ::pyo3_stub_gen::TypeInfo { name: #type_repr.to_string(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) }
+                        || ::pyo3_stub_gen::TypeInfo { name: #type_repr.into(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) }
                     }
                 }
             }
@@ -0,0 +1,34 @@
+use super::{quote_option, StubType};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens, TokenStreamExt};
+
+/// Item with `#[gen_stub(private)]`, which is excluded from `__all__`
+pub struct PrivateInfo {
+    name: String,
+    module: Option<String>,
+}
+
+impl PrivateInfo {
+    pub fn new(name: String, module: Option<String>) -> Self {
+        Self { name, module }
+    }
+}
+
+impl From<&StubType> for PrivateInfo {
+    fn from(stub_type: &StubType) -> Self {
+        Self::new(stub_type.name.clone(), stub_type.module.clone())
+    }
+}
+
+impl ToTokens for PrivateInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self { name, module } = self;
+        let module = quote_option(module);
+        tokens.append_all(quote! {
+            ::pyo3_stub_gen::type_info::PrivateInfo {
+                name: #name,
+                module: #module,
+            }
+        })
+    }
+}
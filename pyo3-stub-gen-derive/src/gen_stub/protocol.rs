@@ -0,0 +1,248 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote, Attribute, Error, FnArg, Ident, ImplItemFn, ItemTrait, LitStr, Result, Token,
+    TraitItem, Type, Visibility,
+};
+
+use super::{
+    attr, extract_documents, parse_gen_stub_skip, util::quote_option, MemberInfo, MethodInfo,
+    StubType,
+};
+
+/// Rust trait describing a duck-typed Python object, generated as `typing.Protocol`
+pub struct PyProtocolInfo {
+    name: String,
+    /// `dyn Trait`, which is used as the marker type of the protocol
+    trait_type: Type,
+    module: Option<String>,
+    getters: Vec<MemberInfo>,
+    methods: Vec<MethodInfo>,
+    doc: String,
+}
+
+impl From<&PyProtocolInfo> for StubType {
+    fn from(info: &PyProtocolInfo) -> Self {
+        Self {
+            ty: info.trait_type.clone(),
+            name: info.name.clone(),
+            module: info.module.clone(),
+        }
+    }
+}
+
+/// Arguments of `#[gen_stub_protocol(...)]`
+#[derive(Default)]
+struct ProtocolAttr {
+    name: Option<String>,
+    module: Option<String>,
+}
+
+impl Parse for ProtocolAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attr = Self::default();
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if ident == "name" {
+                attr.name = Some(input.parse::<LitStr>()?.value());
+            } else if ident == "module" {
+                attr.module = Some(input.parse::<LitStr>()?.value());
+            } else {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("Unsupported keyword `{ident}`, valid is `name` or `module`"),
+                ));
+            }
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            } else {
+                break;
+            }
+        }
+        Ok(attr)
+    }
+}
+
+impl PyProtocolInfo {
+    pub fn new(attr: TokenStream2, item: &ItemTrait) -> Result<Self> {
+        let attr: ProtocolAttr = syn::parse2(attr)?;
+        let ItemTrait {
+            ident,
+            attrs,
+            generics,
+            items,
+            ..
+        } = item;
+        if !generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                generics,
+                "#[gen_stub_protocol] does not support generic traits",
+            ));
+        }
+        let trait_type: Type = parse_quote!(dyn #ident);
+        let mut getters = Vec::new();
+        let mut methods = Vec::new();
+        for item in items {
+            let TraitItem::Fn(item_fn) = item else {
+                continue;
+            };
+            if parse_gen_stub_skip(&item_fn.attrs)? {
+                continue;
+            }
+            // Reuse the parser of `#[pymethods]` by regarding the declaration as a method with an empty body
+            let item_fn = ImplItemFn {
+                attrs: item_fn.attrs.clone(),
+                vis: Visibility::Inherited,
+                defaultness: None,
+                sig: item_fn.sig.clone(),
+                block: parse_quote!({}),
+            };
+            if MemberInfo::is_getter(&item_fn.attrs)? {
+                getters.push(MemberInfo::new_getter(item_fn)?);
+                continue;
+            }
+            let mut method = MethodInfo::try_from(item_fn)?;
+            method.replace_self(&trait_type);
+            methods.push(method);
+        }
+        Ok(Self {
+            name: attr.name.unwrap_or_else(|| ident.to_string()),
+            trait_type,
+            module: attr.module,
+            getters,
+            methods,
+            doc: extract_documents(attrs).join("\n"),
+        })
+    }
+}
+
+impl ToTokens for PyProtocolInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            name,
+            trait_type: _,
+            module,
+            getters,
+            methods,
+            doc,
+        } = self;
+        let module = quote_option(module);
+        tokens.append_all(quote! {
+            ::pyo3_stub_gen::type_info::PyProtocolInfo {
+                name: #name,
+                module: #module,
+                doc: #doc,
+                getters: &[ #(#getters),* ],
+                methods: &[ #(#methods),* ],
+            }
+        })
+    }
+}
+
+/// Remove the attributes for the stub generation, which are not consumed by PyO3 on a trait
+fn prune_pyo3_attrs(attrs: &mut Vec<Attribute>) {
+    attr::prune_attrs(attrs);
+    attrs.retain(|attr| {
+        let path = attr.path();
+        !(path.is_ident("pyo3")
+            || path.is_ident("getter")
+            || path.is_ident("staticmethod")
+            || path.is_ident("classmethod"))
+    });
+}
+
+pub fn prune_attrs(item: &mut ItemTrait) {
+    attr::prune_attrs(&mut item.attrs);
+    for inner in item.items.iter_mut() {
+        if let TraitItem::Fn(item_fn) = inner {
+            prune_pyo3_attrs(&mut item_fn.attrs);
+            for arg in item_fn.sig.inputs.iter_mut() {
+                if let FnArg::Typed(pat_type) = arg {
+                    attr::prune_attrs(&mut pat_type.attrs);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    fn format_as_value(tt: TokenStream2) -> String {
+        let ttt = quote! { const _: () = #tt; };
+        let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
+        formatted
+            .trim()
+            .strip_prefix("const _: () = ")
+            .unwrap()
+            .strip_suffix(';')
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_protocol() -> Result<()> {
+        let input: ItemTrait = parse_str(
+            r#"
+            /// Readable stream
+            pub trait Reader {
+                /// Read at most `n` bytes
+                fn read(&self, n: usize) -> Vec<u8>;
+                #[getter]
+                fn closed(&self) -> bool;
+                fn __enter__(&self) -> Self where Self: Sized;
+            }
+            "#,
+        )?;
+        let out = PyProtocolInfo::new(quote! { module = "my_module" }, &input)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyProtocolInfo {
+            name: "Reader",
+            module: Some("my_module"),
+            doc: "Readable stream",
+            getters: &[
+                ::pyo3_stub_gen::type_info::MemberInfo {
+                    name: "closed",
+                    r#type: <bool as ::pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "",
+                    default: None,
+                    deprecated: None,
+                },
+            ],
+            methods: &[
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "read",
+                    args: &[
+                        ::pyo3_stub_gen::type_info::ArgInfo {
+                            name: "n",
+                            r#type: <usize as ::pyo3_stub_gen::PyStubType>::type_input,
+                            signature: None,
+                        },
+                    ],
+                    r#return: <Vec<u8> as pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "Read at most `n` bytes",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                },
+                ::pyo3_stub_gen::type_info::MethodInfo {
+                    name: "__enter__",
+                    args: &[],
+                    r#return: <dyn Reader as pyo3_stub_gen::PyStubType>::type_output,
+                    doc: "",
+                    r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                },
+            ],
+        }
+        "###);
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use super::{extract_documents, parse_pyo3_attrs, util::quote_option, Attr, MemberInfo, StubType};
+use super::{
+    extract_documents, parse_gen_stub_generic, parse_pyo3_attrs, util::quote_option, Attr,
+    MemberInfo, StubType,
+};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{parse_quote, Error, ItemStruct, Result, Type};
@@ -11,6 +14,8 @@ pub struct PyClassInfo {
     setters: Vec<MemberInfo>,
     doc: String,
     bases: Vec<Type>,
+    /// Type variables given by `#[gen_stub(generic = (T, ...))]`
+    generic: Vec<String>,
     has_eq: bool,
     has_ord: bool,
     has_hash: bool,
@@ -80,6 +85,7 @@ impl TryFrom<ItemStruct> for PyClassInfo {
             }
         }
         let doc = extract_documents(&attrs).join("\n");
+        let generic = parse_gen_stub_generic(&attrs)?;
         Ok(Self {
             struct_type,
             pyclass_name,
@@ -88,6 +94,7 @@ impl TryFrom<ItemStruct> for PyClassInfo {
             module,
             doc,
             bases,
+            generic,
             has_eq,
             has_ord,
             has_hash,
@@ -106,6 +113,7 @@ impl ToTokens for PyClassInfo {
             doc,
             module,
             bases,
+            generic,
             has_eq,
             has_ord,
             has_hash,
@@ -121,6 +129,7 @@ impl ToTokens for PyClassInfo {
                 module: #module,
                 doc: #doc,
                 bases: &[ #( <#bases as ::pyo3_stub_gen::PyStubType>::type_output ),* ],
+                type_params: &[ #(#generic),* ],
                 has_eq: #has_eq,
                 has_ord: #has_ord,
                 has_hash: #has_hash,
@@ -196,6 +205,36 @@ mod test {
             module: Some("my_module"),
             doc: "",
             bases: &[],
+            type_params: &[],
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pyclass_generic() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(generic, extends = Base)]
+            #[gen_stub(generic = (K, V))]
+            pub struct Store {}
+            "#,
+        )?;
+        let out = PyClassInfo::try_from(input)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyClassInfo {
+            pyclass_name: "Store",
+            struct_id: std::any::TypeId::of::<Store>,
+            getters: &[],
+            setters: &[],
+            module: None,
+            doc: "",
+            bases: &[<Base as ::pyo3_stub_gen::PyStubType>::type_output],
+            type_params: &["K", "V"],
             has_eq: false,
             has_ord: false,
             has_hash: false,
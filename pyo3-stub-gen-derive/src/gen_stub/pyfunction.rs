@@ -10,7 +10,7 @@ use crate::gen_stub::util::TypeOrOverride;
 use super::{
     attr::IgnoreTarget, extract_deprecated, extract_documents, extract_return_type, parse_args,
     parse_gen_stub_type_ignore, parse_pyo3_attrs, quote_option, ArgInfo, ArgsWithSignature, Attr,
-    DeprecatedInfo, Signature,
+    AttributeLocation, DeprecatedInfo, PrivateInfo, Signature,
 };
 
 pub struct PyFunctionInfo {
@@ -42,6 +42,10 @@ impl Parse for ModuleAttr {
 }
 
 impl PyFunctionInfo {
+    pub fn private_info(&self) -> PrivateInfo {
+        PrivateInfo::new(self.name.clone(), self.module.clone())
+    }
+
     pub fn parse_attr(&mut self, attr: TokenStream2) -> Result<()> {
         if attr.is_empty() {
             return Ok(());
@@ -57,7 +61,7 @@ impl TryFrom<ItemFn> for PyFunctionInfo {
     fn try_from(item: ItemFn) -> Result<Self> {
         let doc = extract_documents(&item.attrs).join("\n");
         let deprecated = extract_deprecated(&item.attrs);
-        let type_ignored = parse_gen_stub_type_ignore(&item.attrs)?;
+        let type_ignored = parse_gen_stub_type_ignore(&item.attrs, AttributeLocation::Function)?;
         let args = parse_args(item.sig.inputs)?;
         let r#return = extract_return_type(&item.sig.output, &item.attrs)?;
         let mut name = None;
@@ -108,7 +112,7 @@ impl ToTokens for PyFunctionInfo {
                 } => {
                     let imports = imports.iter().collect::<Vec<&String>>();
                     quote! {
-                        || ::pyo3_stub_gen::TypeInfo { name: #type_repr.to_string(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) }
+                        || ::pyo3_stub_gen::TypeInfo { name: #type_repr.into(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) }
                     }
                 }
             }
@@ -149,6 +153,7 @@ impl ToTokens for PyFunctionInfo {
                 is_async: #is_async,
                 deprecated: #deprecated_tt,
                 type_ignored: #type_ignored_tt,
+                location: ::std::panic::Location::caller(),
             }
         })
     }
@@ -76,10 +76,12 @@ impl ToTokens for PyMethodsInfo {
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyMethodsInfo {
                 struct_id: std::any::TypeId::of::<#struct_id>,
+                struct_name: std::any::type_name::<#struct_id>,
                 attrs: &[ #(#attrs),* ],
                 getters: &[ #(#getters),* ],
                 setters: &[ #(#setters),* ],
                 methods: &[ #(#methods),* ],
+                location: ::std::panic::Location::caller(),
             }
         })
     }
@@ -107,7 +107,7 @@ impl ToTokens for ArgsWithSignature<'_> {
                             Ok(quote! {
                             ::pyo3_stub_gen::type_info::ArgInfo {
                                 name: #name,
-                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.to_string(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
+                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.into(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
                                 signature: Some(pyo3_stub_gen::type_info::SignatureArg::Ident),
                             }})
                         },
@@ -158,7 +158,7 @@ impl ToTokens for ArgsWithSignature<'_> {
                             Ok(quote! {
                             ::pyo3_stub_gen::type_info::ArgInfo {
                                 name: #name,
-                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.to_string(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
+                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.into(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
                                 signature: Some(pyo3_stub_gen::type_info::SignatureArg::Assign{
                                     default: {
                                         fn _fmt() -> String {
@@ -192,7 +192,7 @@ impl ToTokens for ArgsWithSignature<'_> {
                             Ok(quote! {
                             ::pyo3_stub_gen::type_info::ArgInfo {
                                 name: #name,
-                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.to_string(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
+                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.into(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
                                 signature: Some(pyo3_stub_gen::type_info::SignatureArg::Args),
                             }})
                         },
@@ -213,7 +213,7 @@ impl ToTokens for ArgsWithSignature<'_> {
                             Ok(quote! {
                             ::pyo3_stub_gen::type_info::ArgInfo {
                                 name: #name,
-                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.to_string(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
+                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.into(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
                                 signature: Some(pyo3_stub_gen::type_info::SignatureArg::Keywords),
                             }})
                         },
@@ -242,7 +242,7 @@ impl ToTokens for ArgsWithSignature<'_> {
                             Ok(quote! {
                             ::pyo3_stub_gen::type_info::ArgInfo {
                                 name: #name,
-                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.to_string(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
+                                r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_repr.into(), import: ::std::collections::HashSet::from([#(#imports.into(),)*]) },
                                 signature: None,
                             }})
                         },
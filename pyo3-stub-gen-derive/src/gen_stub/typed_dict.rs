@@ -0,0 +1,338 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote, Error, Fields, GenericParam, Ident, ItemStruct, LitBool, LitStr, Result, Token,
+    Type,
+};
+
+use super::{
+    extract_documents, parse_gen_stub_skip, parse_pyo3_attrs, remove_lifetime, util::quote_option,
+    Attr, StubType,
+};
+
+/// Rust struct converted from a Python dict by `#[derive(FromPyObject)]`, generated as `typing.TypedDict`
+pub struct PyTypedDictInfo {
+    name: String,
+    /// The struct type with the lifetimes replaced by `'_`
+    struct_type: Type,
+    module: Option<String>,
+    total: bool,
+    fields: Vec<TypedDictField>,
+    doc: String,
+}
+
+struct TypedDictField {
+    /// Key of the dict
+    name: String,
+    /// Type of the value, i.e. `T | None` for `Option<T>`
+    r#type: Type,
+    /// `false` for a field with `#[pyo3(default)]`, which may be missing in the dict
+    required: bool,
+    doc: String,
+}
+
+impl From<&PyTypedDictInfo> for StubType {
+    fn from(info: &PyTypedDictInfo) -> Self {
+        Self {
+            ty: info.struct_type.clone(),
+            name: info.name.clone(),
+            module: info.module.clone(),
+        }
+    }
+}
+
+/// Arguments of `#[gen_stub_typeddict(...)]`
+#[derive(Default)]
+struct TypedDictAttr {
+    name: Option<String>,
+    module: Option<String>,
+    total: Option<bool>,
+}
+
+impl Parse for TypedDictAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attr = Self::default();
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if ident == "name" {
+                attr.name = Some(input.parse::<LitStr>()?.value());
+            } else if ident == "module" {
+                attr.module = Some(input.parse::<LitStr>()?.value());
+            } else if ident == "total" {
+                attr.total = Some(input.parse::<LitBool>()?.value);
+            } else {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("Unsupported keyword `{ident}`, valid is `name`, `module`, or `total`"),
+                ));
+            }
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            } else {
+                break;
+            }
+        }
+        Ok(attr)
+    }
+}
+
+impl PyTypedDictInfo {
+    pub fn new(attr: TokenStream2, item: &ItemStruct) -> Result<Self> {
+        let attr: TypedDictAttr = syn::parse2(attr)?;
+        let ItemStruct {
+            ident,
+            attrs,
+            generics,
+            fields,
+            ..
+        } = item;
+        let mut lifetimes = Vec::new();
+        for param in &generics.params {
+            match param {
+                GenericParam::Lifetime(_) => lifetimes.push(quote! { '_ }),
+                _ => {
+                    return Err(Error::new_spanned(
+                        param,
+                        "#[gen_stub_typeddict] does not support generic structs",
+                    ))
+                }
+            }
+        }
+        let struct_type = if lifetimes.is_empty() {
+            parse_quote!(#ident)
+        } else {
+            parse_quote!(#ident<#(#lifetimes),*>)
+        };
+        let Fields::Named(fields) = fields else {
+            return Err(Error::new_spanned(
+                ident,
+                "#[gen_stub_typeddict] only supports structs with named fields",
+            ));
+        };
+        let total = attr.total.unwrap_or(true);
+        let from_item_all = parse_pyo3_attrs(attrs)?
+            .iter()
+            .any(|attr| matches!(attr, Attr::FromItemAll));
+        let mut typed_dict_fields = Vec::new();
+        for field in &fields.named {
+            let mut name = None;
+            let mut is_item = from_item_all;
+            let mut has_default = false;
+            for attr in parse_pyo3_attrs(&field.attrs)? {
+                match attr {
+                    Attr::Item(key) => {
+                        is_item = true;
+                        if key.is_some() {
+                            name = key;
+                        }
+                    }
+                    Attr::Default => has_default = true,
+                    _ => {}
+                }
+            }
+            // PyO3 reads the other fields by `getattr`, which a dict does not satisfy
+            if !is_item {
+                return Err(Error::new_spanned(
+                    field,
+                    "#[gen_stub_typeddict] requires `#[pyo3(from_item_all)]` on the struct or `#[pyo3(item)]` on the field",
+                ));
+            }
+            if parse_gen_stub_skip(&field.attrs)? {
+                continue;
+            }
+            let field_ident = field.ident.as_ref().expect("named field");
+            let name = name.unwrap_or_else(|| field_ident.to_string());
+            // PyO3 raises `KeyError` for a missing key even for `Option<T>` unless `#[pyo3(default)]` is given
+            let mut r#type = field.ty.clone();
+            remove_lifetime(&mut r#type);
+            typed_dict_fields.push(TypedDictField {
+                name,
+                r#type,
+                required: !has_default,
+                doc: extract_documents(&field.attrs).join("\n"),
+            });
+        }
+        Ok(Self {
+            name: attr.name.unwrap_or_else(|| ident.to_string()),
+            struct_type,
+            module: attr.module,
+            total,
+            fields: typed_dict_fields,
+            doc: extract_documents(attrs).join("\n"),
+        })
+    }
+}
+
+impl ToTokens for TypedDictField {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            name,
+            r#type,
+            required,
+            doc,
+        } = self;
+        tokens.append_all(quote! {
+            ::pyo3_stub_gen::type_info::TypedDictFieldInfo {
+                name: #name,
+                r#type: <#r#type as ::pyo3_stub_gen::PyStubType>::type_input,
+                required: #required,
+                doc: #doc,
+            }
+        })
+    }
+}
+
+impl ToTokens for PyTypedDictInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            name,
+            struct_type: _,
+            module,
+            total,
+            fields,
+            doc,
+        } = self;
+        let module = quote_option(module);
+        tokens.append_all(quote! {
+            ::pyo3_stub_gen::type_info::PyTypedDictInfo {
+                name: #name,
+                module: #module,
+                doc: #doc,
+                total: #total,
+                fields: &[ #(#fields),* ],
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    fn format_as_value(tt: TokenStream2) -> String {
+        let ttt = quote! { const _: () = #tt; };
+        let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
+        formatted
+            .trim()
+            .strip_prefix("const _: () = ")
+            .unwrap()
+            .strip_suffix(';')
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_typed_dict() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            /// Options of the solver
+            #[derive(FromPyObject)]
+            #[pyo3(from_item_all)]
+            pub struct SolverOptions {
+                /// Maximum number of iterations
+                max_iter: usize,
+                #[pyo3(item("tolerance"))]
+                tol: Option<f64>,
+                #[pyo3(default)]
+                verbose: bool,
+            }
+            "#,
+        )?;
+        let out = PyTypedDictInfo::new(quote! { module = "my_module" }, &input)?.to_token_stream();
+        insta::assert_snapshot!(format_as_value(out), @r###"
+        ::pyo3_stub_gen::type_info::PyTypedDictInfo {
+            name: "SolverOptions",
+            module: Some("my_module"),
+            doc: "Options of the solver",
+            total: true,
+            fields: &[
+                ::pyo3_stub_gen::type_info::TypedDictFieldInfo {
+                    name: "max_iter",
+                    r#type: <usize as ::pyo3_stub_gen::PyStubType>::type_input,
+                    required: true,
+                    doc: "Maximum number of iterations",
+                },
+                ::pyo3_stub_gen::type_info::TypedDictFieldInfo {
+                    name: "tolerance",
+                    r#type: <Option<f64> as ::pyo3_stub_gen::PyStubType>::type_input,
+                    required: true,
+                    doc: "",
+                },
+                ::pyo3_stub_gen::type_info::TypedDictFieldInfo {
+                    name: "verbose",
+                    r#type: <bool as ::pyo3_stub_gen::PyStubType>::type_input,
+                    required: false,
+                    doc: "",
+                },
+            ],
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_dict_generics() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[derive(FromPyObject)]
+            #[pyo3(from_item_all)]
+            pub struct Options<'py> {
+                callback: Bound<'py, PyAny>,
+            }
+            "#,
+        )?;
+        let info = PyTypedDictInfo::new(quote! {}, &input)?;
+        let struct_type = &info.struct_type;
+        assert_eq!(quote! { #struct_type }.to_string(), "Options < '_ >");
+
+        let input: ItemStruct = parse_str("pub struct Options<T> { value: T }")?;
+        let err = PyTypedDictInfo::new(quote! {}, &input).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "#[gen_stub_typeddict] does not support generic structs"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_dict_item() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[derive(FromPyObject)]
+            pub struct Options {
+                #[pyo3(item)]
+                max_iter: usize,
+                #[pyo3(item("tol"))]
+                tolerance: f64,
+            }
+            "#,
+        )?;
+        let info = PyTypedDictInfo::new(quote! {}, &input)?;
+        let names: Vec<_> = info
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(names, ["max_iter", "tol"]);
+
+        let input: ItemStruct = parse_str(
+            r#"
+            #[derive(FromPyObject)]
+            pub struct Options {
+                #[pyo3(item)]
+                max_iter: usize,
+                tolerance: f64,
+            }
+            "#,
+        )?;
+        let err = PyTypedDictInfo::new(quote! {}, &input).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "#[gen_stub_typeddict] requires `#[pyo3(from_item_all)]` on the struct or `#[pyo3(item)]` on the field"
+        );
+        Ok(())
+    }
+}
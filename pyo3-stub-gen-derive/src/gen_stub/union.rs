@@ -0,0 +1,383 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote, Error, Fields, GenericParam, Ident, ItemEnum, LitStr, Result, Token, Type,
+};
+
+use super::{
+    attr::{self, OverrideTypeAttribute},
+    extract_documents, parse_gen_stub_variant_override_type, parse_pyo3_attrs, remove_lifetime,
+    util::quote_option,
+    Attr,
+};
+
+/// Rust enum with `#[derive(FromPyObject)]`, whose stub type is the union of the variants
+pub struct UnionInfo {
+    /// The enum type with the lifetimes replaced by `'_`
+    enum_type: Type,
+    variants: Vec<VariantType>,
+    /// Name of the type alias `Name = A | B` if it is emitted
+    alias: Option<String>,
+    module: Option<String>,
+    doc: String,
+}
+
+/// Python type accepted by a variant of `#[derive(FromPyObject)]` enum
+enum VariantType {
+    /// Newtype or `#[pyo3(transparent)]` variant extracted as its field
+    Field(Type),
+    /// Tuple variant extracted from a tuple
+    Tuple(Vec<Type>),
+    /// Struct variant extracted from the items of a mapping by `#[pyo3(item)]`
+    Mapping,
+    /// Struct variant extracted from the attributes of any object
+    Object,
+    /// `#[gen_stub(override_type(...))]`
+    Override(OverrideTypeAttribute),
+}
+
+impl VariantType {
+    fn new(variant: &syn::Variant) -> Result<Self> {
+        if let Some(attr) = parse_gen_stub_variant_override_type(&variant.attrs)? {
+            return Ok(Self::Override(attr));
+        }
+        let variant_attrs = parse_pyo3_attrs(&variant.attrs)?;
+        let field_type = |field: &syn::Field| {
+            let mut ty = field.ty.clone();
+            remove_lifetime(&mut ty);
+            ty
+        };
+        match &variant.fields {
+            Fields::Unit => Err(Error::new_spanned(
+                variant,
+                "#[gen_stub_union] does not support unit variants",
+            )),
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Ok(Self::Field(field_type(&fields.unnamed[0])))
+            }
+            Fields::Unnamed(fields) => {
+                Ok(Self::Tuple(fields.unnamed.iter().map(field_type).collect()))
+            }
+            Fields::Named(fields) => {
+                if variant_attrs.contains(&Attr::Transparent) && fields.named.len() == 1 {
+                    return Ok(Self::Field(field_type(&fields.named[0])));
+                }
+                if variant_attrs.contains(&Attr::FromItemAll) {
+                    return Ok(Self::Mapping);
+                }
+                for field in &fields.named {
+                    let is_item = parse_pyo3_attrs(&field.attrs)?
+                        .iter()
+                        .any(|attr| matches!(attr, Attr::Item(_)));
+                    if !is_item {
+                        return Ok(Self::Object);
+                    }
+                }
+                Ok(Self::Mapping)
+            }
+        }
+    }
+
+    /// `TypeInfo` of this variant by `method`, i.e. `type_input` or `type_output`
+    fn type_info(&self, method: &Ident) -> TokenStream2 {
+        match self {
+            Self::Field(ty) => quote! { <#ty as ::pyo3_stub_gen::PyStubType>::#method() },
+            Self::Tuple(types) => quote! {
+                ::pyo3_stub_gen::TypeInfo::generic(
+                    ::pyo3_stub_gen::TypeExpr::qualified("builtins", "tuple"),
+                    [ #(<#types as ::pyo3_stub_gen::PyStubType>::#method()),* ],
+                )
+            },
+            Self::Mapping => quote! {
+                ::pyo3_stub_gen::TypeInfo::generic(
+                    ::pyo3_stub_gen::TypeExpr::qualified("typing", "Mapping"),
+                    [::pyo3_stub_gen::TypeInfo::builtin("str"), ::pyo3_stub_gen::TypeInfo::any()],
+                )
+            },
+            Self::Object => quote! { ::pyo3_stub_gen::TypeInfo::any() },
+            Self::Override(OverrideTypeAttribute { type_repr, imports }) => {
+                let imports = imports.iter().collect::<Vec<_>>();
+                quote! {
+                    ::pyo3_stub_gen::TypeInfo {
+                        name: #type_repr.into(),
+                        import: ::std::collections::HashSet::from([#(#imports.into(),)*]),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Arguments of `#[gen_stub_union(...)]`
+#[derive(Default)]
+struct UnionAttr {
+    /// `alias` or `alias = "Name"`
+    alias: Option<Option<String>>,
+    module: Option<String>,
+}
+
+impl Parse for UnionAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attr = Self::default();
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            if ident == "alias" {
+                attr.alias = Some(if input.peek(Token![=]) {
+                    input.parse::<Token![=]>()?;
+                    Some(input.parse::<LitStr>()?.value())
+                } else {
+                    None
+                });
+            } else if ident == "module" {
+                input.parse::<Token![=]>()?;
+                attr.module = Some(input.parse::<LitStr>()?.value());
+            } else {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("Unsupported keyword `{ident}`, valid is `alias`, `alias = \"...\"`, or `module = \"...\"`"),
+                ));
+            }
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            } else {
+                break;
+            }
+        }
+        Ok(attr)
+    }
+}
+
+impl UnionInfo {
+    pub fn new(attr: TokenStream2, item: &ItemEnum) -> Result<Self> {
+        let attr: UnionAttr = syn::parse2(attr)?;
+        let ident = &item.ident;
+        let mut lifetimes = Vec::new();
+        for param in &item.generics.params {
+            match param {
+                GenericParam::Lifetime(_) => lifetimes.push(quote! { '_ }),
+                _ => {
+                    return Err(Error::new_spanned(
+                        param,
+                        "#[gen_stub_union] does not support generic enums",
+                    ))
+                }
+            }
+        }
+        let enum_type = if lifetimes.is_empty() {
+            parse_quote!(#ident)
+        } else {
+            parse_quote!(#ident<#(#lifetimes),*>)
+        };
+        if attr.module.is_some() && attr.alias.is_none() {
+            return Err(Error::new_spanned(
+                ident,
+                "`module` is only valid with `alias`",
+            ));
+        }
+        Ok(Self {
+            enum_type,
+            variants: item
+                .variants
+                .iter()
+                .map(VariantType::new)
+                .collect::<Result<_>>()?,
+            alias: attr
+                .alias
+                .map(|alias| alias.unwrap_or_else(|| ident.to_string())),
+            module: attr.module,
+            doc: extract_documents(&item.attrs).join("\n"),
+        })
+    }
+
+    fn union(&self, method: &str) -> TokenStream2 {
+        let method = Ident::new(method, proc_macro2::Span::call_site());
+        let members = self
+            .variants
+            .iter()
+            .map(|variant| variant.type_info(&method));
+        quote! { ::pyo3_stub_gen::TypeInfo::union([ #(#members),* ]) }
+    }
+}
+
+impl ToTokens for UnionInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            enum_type,
+            alias,
+            module,
+            doc,
+            ..
+        } = self;
+        let Some(alias) = alias else {
+            let output = self.union("type_output");
+            let input = self.union("type_input");
+            tokens.append_all(quote! {
+                #[automatically_derived]
+                impl ::pyo3_stub_gen::PyStubType for #enum_type {
+                    fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                        #output
+                    }
+                    fn type_input() -> ::pyo3_stub_gen::TypeInfo {
+                        #input
+                    }
+                }
+            });
+            return;
+        };
+        let module_tt = if let Some(module) = module {
+            quote! { #module.into() }
+        } else {
+            quote! { Default::default() }
+        };
+        let input = self.union("type_input");
+        let module = quote_option(module);
+        tokens.append_all(quote! {
+            #[automatically_derived]
+            impl ::pyo3_stub_gen::PyStubType for #enum_type {
+                fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                    ::pyo3_stub_gen::TypeInfo::locally_defined(#alias, #module_tt)
+                }
+            }
+            pyo3_stub_gen::inventory::submit! {
+                ::pyo3_stub_gen::type_info::PyTypeAliasInfo {
+                    name: #alias,
+                    module: #module,
+                    doc: #doc,
+                    r#type: || #input,
+                }
+            }
+        })
+    }
+}
+
+pub fn prune_attrs(item: &mut ItemEnum) {
+    attr::prune_attrs(&mut item.attrs);
+    for variant in &mut item.variants {
+        attr::prune_attrs(&mut variant.attrs);
+        for field in variant.fields.iter_mut() {
+            attr::prune_attrs(&mut field.attrs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    fn format(tt: TokenStream2) -> String {
+        prettyplease::unparse(&syn::parse_file(&tt.to_string()).unwrap())
+    }
+
+    #[test]
+    fn test_union() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            #[derive(FromPyObject)]
+            pub enum Input<'py> {
+                Int(i64),
+                Str(Bound<'py, PyString>),
+                Pair(f64, f64),
+                #[pyo3(transparent)]
+                Path { path: PathBuf },
+                Point {
+                    #[pyo3(item)]
+                    x: f64,
+                    #[pyo3(item("y"))]
+                    y: f64,
+                },
+                #[gen_stub(override_type(type_repr = "numpy.ndarray", imports = ("numpy")))]
+                Array { shape: Vec<usize> },
+            }
+            "#,
+        )?;
+        let out = UnionInfo::new(quote! {}, &input)?.to_token_stream();
+        insta::assert_snapshot!(format(out), @r###"
+        #[automatically_derived]
+        impl ::pyo3_stub_gen::PyStubType for Input<'_> {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::union([
+                    <i64 as ::pyo3_stub_gen::PyStubType>::type_output(),
+                    <Bound<'_, PyString> as ::pyo3_stub_gen::PyStubType>::type_output(),
+                    ::pyo3_stub_gen::TypeInfo::generic(
+                        ::pyo3_stub_gen::TypeExpr::qualified("builtins", "tuple"),
+                        [
+                            <f64 as ::pyo3_stub_gen::PyStubType>::type_output(),
+                            <f64 as ::pyo3_stub_gen::PyStubType>::type_output(),
+                        ],
+                    ),
+                    <PathBuf as ::pyo3_stub_gen::PyStubType>::type_output(),
+                    ::pyo3_stub_gen::TypeInfo::generic(
+                        ::pyo3_stub_gen::TypeExpr::qualified("typing", "Mapping"),
+                        [
+                            ::pyo3_stub_gen::TypeInfo::builtin("str"),
+                            ::pyo3_stub_gen::TypeInfo::any(),
+                        ],
+                    ),
+                    ::pyo3_stub_gen::TypeInfo {
+                        name: "numpy.ndarray".into(),
+                        import: ::std::collections::HashSet::from(["numpy".into()]),
+                    },
+                ])
+            }
+            fn type_input() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::union([
+                    <i64 as ::pyo3_stub_gen::PyStubType>::type_input(),
+                    <Bound<'_, PyString> as ::pyo3_stub_gen::PyStubType>::type_input(),
+                    ::pyo3_stub_gen::TypeInfo::generic(
+                        ::pyo3_stub_gen::TypeExpr::qualified("builtins", "tuple"),
+                        [
+                            <f64 as ::pyo3_stub_gen::PyStubType>::type_input(),
+                            <f64 as ::pyo3_stub_gen::PyStubType>::type_input(),
+                        ],
+                    ),
+                    <PathBuf as ::pyo3_stub_gen::PyStubType>::type_input(),
+                    ::pyo3_stub_gen::TypeInfo::generic(
+                        ::pyo3_stub_gen::TypeExpr::qualified("typing", "Mapping"),
+                        [
+                            ::pyo3_stub_gen::TypeInfo::builtin("str"),
+                            ::pyo3_stub_gen::TypeInfo::any(),
+                        ],
+                    ),
+                    ::pyo3_stub_gen::TypeInfo {
+                        name: "numpy.ndarray".into(),
+                        import: ::std::collections::HashSet::from(["numpy".into()]),
+                    },
+                ])
+            }
+        }
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_union_alias() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            /// Number accepted by the functions
+            #[derive(FromPyObject)]
+            pub enum Number {
+                Int(i64),
+                Float(f64),
+            }
+            "#,
+        )?;
+        let out = UnionInfo::new(quote! { alias, module = "my_module" }, &input)?.to_token_stream();
+        insta::assert_snapshot!(format(out), @r###"
+        #[automatically_derived]
+        impl ::pyo3_stub_gen::PyStubType for Number {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                ::pyo3_stub_gen::TypeInfo::locally_defined("Number", "my_module".into())
+            }
+        }
+        pyo3_stub_gen::inventory::submit! {
+            ::pyo3_stub_gen::type_info::PyTypeAliasInfo { name : "Number", module :
+            Some("my_module"), doc : "Number accepted by the functions", r#type : ||
+            ::pyo3_stub_gen::TypeInfo::union([< i64 as ::pyo3_stub_gen::PyStubType >
+            ::type_input(), < f64 as ::pyo3_stub_gen::PyStubType > ::type_input()]), }
+        }
+        "###);
+        Ok(())
+    }
+}
@@ -65,6 +65,123 @@ pub fn gen_stub_pyclass_complex_enum(_attr: TokenStream, item: TokenStream) -> T
         .into()
 }
 
+/// Generate `typing.TypedDict` for a Rust struct converted from a Python dict by `#[derive(FromPyObject)]`
+///
+/// The struct must be read from a dict, i.e. have `#[pyo3(from_item_all)]` or `#[pyo3(item)]` on every field,
+/// since PyO3 reads the other fields as attributes.
+/// The keys are the field names or `#[pyo3(item("key"))]`, and the value types are given by [PyStubType],
+/// e.g. `T | None` for `Option<T>`.
+/// Only `#[pyo3(default)]` fields may be missing in the dict as PyO3 requires the other keys even for `Option<T>`,
+/// and they are written as `NotRequired[T]`, or all the other fields are written as `Required[T]` with `total = false`.
+/// The struct also implements [PyStubType] so that it can be used as an argument type.
+///
+/// ```
+/// #[pyo3_stub_gen_derive::gen_stub_typeddict(module = "my_module", total = true)]
+/// #[derive(pyo3::FromPyObject)]
+/// #[pyo3(from_item_all)]
+/// pub struct SolverOptions {
+///     /// Maximum number of iterations
+///     max_iter: usize,
+///     #[pyo3(item("tolerance"), default)]
+///     tol: Option<f64>,
+/// }
+/// ```
+///
+/// [PyStubType]: https://docs.rs/pyo3-stub-gen/latest/pyo3_stub_gen/trait.PyStubType.html
+#[proc_macro_attribute]
+pub fn gen_stub_typeddict(attr: TokenStream, item: TokenStream) -> TokenStream {
+    gen_stub::typed_dict(attr.into(), item.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implement [PyStubType] of a fieldless enum as `typing.Literal[...]` of its variants,
+/// e.g. for an enum extracted from a string by a custom `FromPyObject`.
+///
+/// The variant names are used as the string values, renamed by `rename_all` if given.
+/// `int` uses the discriminants instead, and `#[gen_stub(literal = ...)]` overrides the value of a variant.
+///
+/// ```
+/// #[pyo3_stub_gen_derive::gen_stub_literal(rename_all = "lowercase")]
+/// pub enum Mode {
+///     Fast,
+///     Accurate,
+///     #[gen_stub(literal = "auto-detect")]
+///     Auto,
+/// }
+///
+/// #[pyo3_stub_gen_derive::gen_stub_literal(int)]
+/// pub enum Level {
+///     Low = 1,
+///     High,
+/// }
+/// ```
+///
+/// [PyStubType]: https://docs.rs/pyo3-stub-gen/latest/pyo3_stub_gen/trait.PyStubType.html
+#[proc_macro_attribute]
+pub fn gen_stub_literal(attr: TokenStream, item: TokenStream) -> TokenStream {
+    gen_stub::literal(attr.into(), item.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implement [PyStubType] of an enum with `#[derive(FromPyObject)]` as the union of its variants.
+///
+/// - A newtype or `#[pyo3(transparent)]` variant is its field type
+/// - A tuple variant is `tuple[...]` of its fields
+/// - A struct variant is `typing.Mapping[str, typing.Any]` if all fields are extracted by `#[pyo3(item)]`,
+///   and `typing.Any` otherwise
+/// - `#[gen_stub(override_type(type_repr = ..., imports = (...)))]` overrides the type of a variant
+///
+/// `alias` emits a type alias `Number: typing.TypeAlias = int | float` named as the enum,
+/// or by `alias = "..."`, into the default module or `module = "..."`, and the enum is referred by the alias.
+///
+/// ```
+/// #[pyo3_stub_gen_derive::gen_stub_union(alias, module = "my_module")]
+/// #[derive(pyo3::FromPyObject)]
+/// pub enum Number {
+///     Int(i64),
+///     Float(f64),
+/// }
+/// ```
+///
+/// [PyStubType]: https://docs.rs/pyo3-stub-gen/latest/pyo3_stub_gen/trait.PyStubType.html
+#[proc_macro_attribute]
+pub fn gen_stub_union(attr: TokenStream, item: TokenStream) -> TokenStream {
+    gen_stub::union(attr.into(), item.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Generate `typing.Protocol` from a Rust trait describing duck-typed Python objects,
+/// and implement [PyStubType] of `dyn Trait` as the protocol.
+///
+/// The methods are parsed as in `#[gen_stub_pymethods]`, e.g. `#[getter]` declares a property,
+/// and `Self` refers the protocol itself. The trait is never called from Rust,
+/// and only the declaration is used for the stub file.
+/// Use `PyProtocol<'py, dyn Trait>` for arguments accepting such objects.
+///
+/// ```
+/// #[pyo3_stub_gen_derive::gen_stub_protocol(module = "my_module")]
+/// pub trait Reader {
+///     /// Read at most `n` bytes
+///     fn read(&self, n: usize) -> Vec<u8>;
+///     #[getter]
+///     fn closed(&self) -> bool;
+/// }
+/// ```
+///
+/// Since `dyn Trait` is used as the marker type, the trait must be dyn compatible,
+/// e.g. a method returning `Self` requires `where Self: Sized`.
+///
+/// [PyStubType]: https://docs.rs/pyo3-stub-gen/latest/pyo3_stub_gen/trait.PyStubType.html
+#[proc_macro_attribute]
+pub fn gen_stub_protocol(attr: TokenStream, item: TokenStream) -> TokenStream {
+    gen_stub::protocol(attr.into(), item.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Embed metadata for Python stub file generation for `#[pymethods]` macro
 ///
 /// ```
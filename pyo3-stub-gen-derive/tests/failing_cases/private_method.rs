@@ -0,0 +1,15 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen_derive::*;
+
+#[gen_stub_pyclass]
+#[pyclass]
+struct A {}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl A {
+    #[gen_stub(private)]
+    fn f(&self) {}
+}
+
+fn main() {}
@@ -31,6 +31,7 @@ macro_rules! create_exception {
                 module: Some(stringify!($module)),
                 doc: $doc,
                 bases: &[|| <$base as $crate::PyStubType>::type_output()],
+                type_params: &[],
                 has_eq: false,
                 has_ord: false,
                 has_hash: false,
@@ -1,28 +1,55 @@
 //! Generate Python typing stub file a.k.a. `*.pyi` file.
 
 mod arg;
+mod check;
 mod class;
+mod custom;
 mod deprecated;
+mod diff;
 mod docstring;
 mod enum_;
+mod fragment;
 mod function;
+mod imports;
 mod member;
 mod method;
 mod module;
+mod orphan;
+mod package;
+mod render;
 mod stub_info;
+mod target;
+mod type_alias;
+mod type_var;
+mod typed_dict;
+mod validate;
 mod variable;
 mod variant_methods;
 
 pub use arg::*;
+pub use check::*;
 pub use class::*;
+pub use deprecated::*;
+pub use diff::*;
 pub use enum_::*;
+pub use fragment::*;
 pub use function::*;
 pub use member::*;
 pub use method::*;
 pub use module::*;
+pub use orphan::*;
+pub use package::*;
+pub use render::*;
 pub use stub_info::*;
+pub use target::*;
+pub use type_alias::*;
+pub use type_var::*;
+pub use typed_dict::*;
+pub use validate::*;
 pub use variable::*;
 
+pub use imports::TypeNameStyle;
+
 use crate::stub_type::ImportRef;
 use std::collections::HashSet;
 
@@ -30,6 +57,7 @@ fn indent() -> &'static str {
     "    "
 }
 
+/// Imports required by an item in a stub file, which depend on the targeted Python version
 pub trait Import {
-    fn import(&self) -> HashSet<ImportRef>;
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef>;
 }
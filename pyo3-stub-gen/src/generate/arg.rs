@@ -1,43 +1,93 @@
-use crate::{generate::Import, stub_type::ImportRef, type_info::*, TypeInfo};
+use crate::{
+    generate::{Import, PythonVersion, Render, RenderContext},
+    stub_type::ImportRef,
+    type_info::*,
+    TypeInfo,
+};
+use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, fmt};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Arg {
-    pub name: &'static str,
+    pub name: String,
     pub r#type: TypeInfo,
-    pub signature: Option<SignatureArg>,
+    pub signature: Option<SignatureArgDef>,
+}
+
+/// [SignatureArg] with the evaluated default value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SignatureArgDef {
+    Ident,
+    Assign {
+        default: String,
+    },
+    Star,
+    /// `*args`. The type is of the whole tuple, and not written.
+    Args,
+    /// `**kwargs`. The type is of the whole dict, and not written.
+    Keywords,
+    /// `/` making the preceding arguments positional-only
+    Slash,
+    /// `*args: T` annotated with the type of each argument, e.g. in a `.pyi` fragment
+    AnnotatedArgs,
+    /// `**kwargs: T` annotated with the type of each argument, e.g. in a `.pyi` fragment
+    AnnotatedKeywords,
+}
+
+impl From<&SignatureArg> for SignatureArgDef {
+    fn from(signature: &SignatureArg) -> Self {
+        match signature {
+            SignatureArg::Ident => Self::Ident,
+            SignatureArg::Assign { default } => Self::Assign { default: default() },
+            SignatureArg::Star => Self::Star,
+            SignatureArg::Args => Self::Args,
+            SignatureArg::Keywords => Self::Keywords,
+        }
+    }
 }
 
 impl Import for Arg {
-    fn import(&self) -> HashSet<ImportRef> {
-        self.r#type.import.clone()
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        self.r#type.import(target)
     }
 }
 
 impl From<&ArgInfo> for Arg {
     fn from(info: &ArgInfo) -> Self {
         Self {
-            name: info.name,
+            name: info.name.to_string(),
             r#type: (info.r#type)(),
-            signature: info.signature.clone(),
+            signature: info.signature.as_ref().map(SignatureArgDef::from),
         }
     }
 }
 
-impl fmt::Display for Arg {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Render for Arg {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        let r#type = ctx.display(&self.r#type);
         if let Some(signature) = &self.signature {
             match signature {
-                SignatureArg::Ident => write!(f, "{}:{}", self.name, self.r#type),
-                SignatureArg::Assign { default } => {
-                    write!(f, "{}:{}={}", self.name, self.r#type, default())
+                SignatureArgDef::Ident => write!(f, "{}:{}", self.name, r#type),
+                SignatureArgDef::Assign { default } => {
+                    write!(f, "{}:{}={}", self.name, r#type, default)
+                }
+                SignatureArgDef::Star => write!(f, "*"),
+                SignatureArgDef::Args => write!(f, "*{}", self.name),
+                SignatureArgDef::Keywords => write!(f, "**{}", self.name),
+                SignatureArgDef::Slash => write!(f, "/"),
+                SignatureArgDef::AnnotatedArgs => write!(f, "*{}:{}", self.name, r#type),
+                SignatureArgDef::AnnotatedKeywords => {
+                    write!(f, "**{}:{}", self.name, r#type)
                 }
-                SignatureArg::Star => write!(f, "*"),
-                SignatureArg::Args => write!(f, "*{}", self.name),
-                SignatureArg::Keywords => write!(f, "**{}", self.name),
             }
         } else {
-            write!(f, "{}:{}", self.name, self.r#type)
+            write!(f, "{}:{}", self.name, r#type)
         }
     }
 }
+
+impl fmt::Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
@@ -0,0 +1,59 @@
+use std::{fmt, path::PathBuf};
+
+/// Result of [crate::StubInfo::check], i.e. the difference between the stub files on disk
+/// and the ones [crate::StubInfo::generate] would write.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CheckReport {
+    /// Stub files which would be generated but do not exist on disk.
+    pub missing: Vec<PathBuf>,
+    /// Generated stub files on disk which are no longer produced.
+    pub extra: Vec<PathBuf>,
+    /// Stub files whose content differs from the generated one.
+    pub changed: Vec<ChangedStub>,
+}
+
+/// A stub file on disk which differs from the generated one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedStub {
+    pub path: PathBuf,
+    /// Unified diff from the file on disk to the generated content.
+    pub diff: String,
+}
+
+impl CheckReport {
+    /// `true` if the stub files on disk are exactly what would be generated.
+    pub fn is_up_to_date(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_up_to_date() {
+            return writeln!(f, "Stub files are up to date.");
+        }
+        writeln!(f, "Stub files are not up to date:")?;
+        for path in &self.missing {
+            writeln!(f, "  missing: {}", path.display())?;
+        }
+        for path in &self.extra {
+            writeln!(f, "  extra:   {}", path.display())?;
+        }
+        for changed in &self.changed {
+            writeln!(f, "  changed: {}", changed.path.display())?;
+        }
+        for changed in &self.changed {
+            writeln!(f)?;
+            write!(f, "{}", changed.diff)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn unified_diff(path: &std::path::Path, current: &str, expected: &str) -> String {
+    let path = path.display().to_string();
+    similar::TextDiff::from_lines(current, expected)
+        .unified_diff()
+        .header(&format!("{path} (on disk)"), &format!("{path} (generated)"))
+        .to_string()
+}
@@ -1,37 +1,56 @@
 use indexmap::IndexMap;
 
 use crate::generate::variant_methods::get_variant_methods;
-use crate::{generate::*, type_info::*, TypeInfo};
+use crate::{generate::*, stub_type::TypeRef, type_info::*, TypeExpr, TypeInfo};
+use serde::{Deserialize, Serialize};
 use std::{fmt, vec};
 
 /// Definition of a Python class.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClassDef {
-    pub name: &'static str,
-    pub doc: &'static str,
+    pub name: String,
+    pub doc: String,
     pub attrs: Vec<MemberDef>,
     pub getter_setters: IndexMap<String, (Option<MemberDef>, Option<MemberDef>)>,
     pub methods: IndexMap<String, Vec<MethodDef>>,
     pub bases: Vec<TypeInfo>,
+    /// Type parameters declared as module-level `TypeVar` or `ParamSpec`,
+    /// written as `class C[T]` since Python 3.12, or by adding `typing.Generic[T]` to the bases before
+    #[serde(default)]
+    pub type_params: Vec<TypeVarDef>,
     pub classes: Vec<ClassDef>,
     pub match_args: Option<Vec<String>>,
 }
 
 impl Import for ClassDef {
-    fn import(&self) -> HashSet<ImportRef> {
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
         let mut import = HashSet::new();
-        for base in &self.bases {
-            import.extend(base.import.clone());
+        for base in self.bases(target) {
+            import.extend(base.import(target));
+        }
+        if target.has_type_parameter_syntax() {
+            for type_param in &self.type_params {
+                import.extend(type_param.bound_import(target));
+            }
+        } else {
+            for type_param in &self.type_params {
+                if let Some(module) = &type_param.module {
+                    import.insert(ImportRef::Type(TypeRef::new(
+                        module.as_str().into(),
+                        type_param.name.clone(),
+                    )));
+                }
+            }
         }
         for attr in &self.attrs {
-            import.extend(attr.import());
+            import.extend(attr.import(target));
         }
         for (getter, setter) in self.getter_setters.values() {
             if let Some(getter) = getter {
-                import.extend(getter.import());
+                import.extend(getter.import(target));
             }
             if let Some(setter) = setter {
-                import.extend(setter.import());
+                import.extend(setter.import(target));
             }
         }
         for method in self.methods.values() {
@@ -40,11 +59,11 @@ impl Import for ClassDef {
                 import.insert("typing".into());
             }
             for method in method {
-                import.extend(method.import());
+                import.extend(method.import(target));
             }
         }
         for class in &self.classes {
-            import.extend(class.import());
+            import.extend(class.import(target));
         }
         import
     }
@@ -56,8 +75,8 @@ impl From<&PyComplexEnumInfo> for ClassDef {
         // This is only an initializer. See `StubInfo::gather` for the actual merging.
 
         let enum_info = Self {
-            name: info.pyclass_name,
-            doc: info.doc,
+            name: info.pyclass_name.to_string(),
+            doc: info.doc.to_string(),
             getter_setters: IndexMap::new(),
             methods: IndexMap::new(),
             classes: info
@@ -66,6 +85,7 @@ impl From<&PyComplexEnumInfo> for ClassDef {
                 .map(|v| ClassDef::from_variant(info, v))
                 .collect(),
             bases: Vec::new(),
+            type_params: Vec::new(),
             match_args: None,
             attrs: Vec::new(),
         };
@@ -79,8 +99,8 @@ impl ClassDef {
         let methods = get_variant_methods(enum_info, info);
 
         Self {
-            name: info.pyclass_name,
-            doc: info.doc,
+            name: info.pyclass_name.to_string(),
+            doc: info.doc.to_string(),
             getter_setters: info
                 .fields
                 .iter()
@@ -89,12 +109,40 @@ impl ClassDef {
             methods,
             classes: Vec::new(),
             bases: vec![TypeInfo::unqualified(enum_info.pyclass_name)],
+            type_params: Vec::new(),
             match_args: Some(info.fields.iter().map(|f| f.name.to_string()).collect()),
             attrs: Vec::new(),
         }
     }
 }
 
+impl From<&PyProtocolInfo> for ClassDef {
+    fn from(info: &PyProtocolInfo) -> Self {
+        let mut methods: IndexMap<String, Vec<MethodDef>> = IndexMap::new();
+        for method in info.methods {
+            methods
+                .entry(method.name.to_string())
+                .or_default()
+                .push(MethodDef::from(method));
+        }
+        Self {
+            name: info.name.to_string(),
+            doc: info.doc.to_string(),
+            attrs: Vec::new(),
+            getter_setters: info
+                .getters
+                .iter()
+                .map(|info| (info.name.to_string(), (Some(MemberDef::from(info)), None)))
+                .collect(),
+            methods,
+            classes: Vec::new(),
+            bases: vec![TypeExpr::qualified("typing", "Protocol").into()],
+            type_params: Vec::new(),
+            match_args: None,
+        }
+    }
+}
+
 impl From<&PyClassInfo> for ClassDef {
     fn from(info: &PyClassInfo) -> Self {
         // Since there are multiple `#[pymethods]` for a single class, we need to merge them.
@@ -105,22 +153,23 @@ impl From<&PyClassInfo> for ClassDef {
             .map(|info| (info.name.to_string(), (Some(MemberDef::from(info)), None)))
             .collect();
         for setter in info.setters {
-            getter_setters.entry(setter.name.to_string()).or_default().1 = Some(MemberDef {
-                name: setter.name,
-                r#type: (setter.r#type)(),
-                doc: setter.doc,
-                default: setter.default.map(|f| f()),
-                deprecated: setter.deprecated.clone(),
-            });
+            getter_setters.entry(setter.name.to_string()).or_default().1 =
+                Some(MemberDef::from(setter));
         }
         let mut new = Self {
-            name: info.pyclass_name,
-            doc: info.doc,
+            name: info.pyclass_name.to_string(),
+            doc: info.doc.to_string(),
             attrs: Vec::new(),
             getter_setters,
             methods: Default::default(),
             classes: Vec::new(),
             bases: info.bases.iter().map(|f| f()).collect(),
+            // Replaced by the declarations in the module, see `StubInfoBuilder::finish`
+            type_params: info
+                .type_params
+                .iter()
+                .map(|name| TypeVarDef::undeclared(name))
+                .collect(),
             match_args: None,
         };
         if info.has_eq {
@@ -141,14 +190,14 @@ impl From<&PyClassInfo> for ClassDef {
 impl ClassDef {
     fn add_eq_method(&mut self) {
         let method = MethodDef {
-            name: "__eq__",
+            name: "__eq__".to_string(),
             args: vec![Arg {
-                name: "other",
+                name: "other".to_string(),
                 r#type: TypeInfo::builtin("object"),
                 signature: None,
             }],
             r#return: TypeInfo::builtin("bool"),
-            doc: "",
+            doc: String::new(),
             r#type: MethodType::Instance,
             is_async: false,
             deprecated: None,
@@ -165,14 +214,14 @@ impl ClassDef {
 
         for name in &ord_methods {
             let method = MethodDef {
-                name,
+                name: name.to_string(),
                 args: vec![Arg {
-                    name: "other",
+                    name: "other".to_string(),
                     r#type: TypeInfo::builtin("object"),
                     signature: None,
                 }],
                 r#return: TypeInfo::builtin("bool"),
-                doc: "",
+                doc: String::new(),
                 r#type: MethodType::Instance,
                 is_async: false,
                 deprecated: None,
@@ -187,10 +236,10 @@ impl ClassDef {
 
     fn add_hash_method(&mut self) {
         let method = MethodDef {
-            name: "__hash__",
+            name: "__hash__".to_string(),
             args: vec![],
             r#return: TypeInfo::builtin("int"),
-            doc: "",
+            doc: String::new(),
             r#type: MethodType::Instance,
             is_async: false,
             deprecated: None,
@@ -204,10 +253,10 @@ impl ClassDef {
 
     fn add_str_method(&mut self) {
         let method = MethodDef {
-            name: "__str__",
+            name: "__str__".to_string(),
             args: vec![],
             r#return: TypeInfo::builtin("str"),
-            doc: "",
+            doc: String::new(),
             r#type: MethodType::Instance,
             is_async: false,
             deprecated: None,
@@ -220,16 +269,119 @@ impl ClassDef {
     }
 }
 
-impl fmt::Display for ClassDef {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl ClassDef {
+    /// Merge the members of `other` with the same name into this class, see [Module::merge].
+    ///
+    /// Members already existing in this class are not merged, and their names are returned as conflicts.
+    pub fn merge(&mut self, other: ClassDef) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        if !other.doc.is_empty() {
+            if self.doc.is_empty() {
+                self.doc = other.doc;
+            } else {
+                conflicts.push("__doc__".to_string());
+            }
+        }
+        for base in other.bases {
+            if !self.bases.iter().any(|b| b.name == base.name) {
+                self.bases.push(base);
+            }
+        }
+        for attr in other.attrs {
+            if self.has_member(&attr.name) {
+                conflicts.push(attr.name);
+            } else {
+                self.attrs.push(attr);
+            }
+        }
+        for (name, (getter, setter)) in other.getter_setters {
+            let is_attr = self.attrs.iter().any(|attr| attr.name == name)
+                || self.methods.contains_key(&name)
+                || self.classes.iter().any(|class| class.name == name);
+            if is_attr {
+                conflicts.push(name);
+                continue;
+            }
+            let existing = self.getter_setters.entry(name.clone()).or_default();
+            if (getter.is_some() && existing.0.is_some())
+                || (setter.is_some() && existing.1.is_some())
+            {
+                conflicts.push(name);
+                continue;
+            }
+            existing.0 = existing.0.take().or(getter);
+            existing.1 = existing.1.take().or(setter);
+        }
+        for (name, methods) in other.methods {
+            if self.has_member(&name) {
+                conflicts.push(name);
+            } else {
+                self.methods.insert(name, methods);
+            }
+        }
+        for class in other.classes {
+            if let Some(existing) = self.classes.iter_mut().find(|c| c.name == class.name) {
+                let prefix = existing.name.clone();
+                conflicts.extend(
+                    existing
+                        .merge(class)
+                        .into_iter()
+                        .map(|name| format!("{prefix}.{name}")),
+                );
+            } else if self.has_member(&class.name) {
+                conflicts.push(class.name);
+            } else {
+                self.classes.push(class);
+            }
+        }
+        conflicts
+    }
+
+    fn has_member(&self, name: &str) -> bool {
+        self.attrs.iter().any(|attr| attr.name == name)
+            || self.getter_setters.contains_key(name)
+            || self.methods.contains_key(name)
+            || self.classes.iter().any(|class| class.name == name)
+    }
+}
+
+impl ClassDef {
+    /// Base classes including `typing.Generic[T, ...]` for the type parameters before Python 3.12
+    fn bases(&self, target: PythonVersion) -> Vec<TypeInfo> {
+        let mut bases = self.bases.clone();
+        if !self.type_params.is_empty() && !target.has_type_parameter_syntax() {
+            bases.push(TypeInfo::generic(
+                TypeExpr::qualified("typing", "Generic"),
+                self.type_params
+                    .iter()
+                    .map(|type_param| TypeInfo::unqualified(&type_param.name)),
+            ));
+        }
+        bases
+    }
+}
+
+impl Render for ClassDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        let type_params = if ctx.target.has_type_parameter_syntax() && !self.type_params.is_empty()
+        {
+            let type_params = self
+                .type_params
+                .iter()
+                .map(|type_param| type_param.type_param(ctx))
+                .collect::<Vec<_>>();
+            format!("[{}]", type_params.join(", "))
+        } else {
+            String::new()
+        };
         let bases = self
-            .bases
+            .bases(ctx.target)
             .iter()
-            .map(|i| i.name.clone())
+            .map(|i| ctx.display(i).to_string())
             .reduce(|acc, path| format!("{acc}, {path}"))
             .map(|bases| format!("({bases})"))
             .unwrap_or_default();
-        writeln!(f, "class {}{}:", self.name, bases)?;
+        writeln!(f, "class {}{}{}:", self.name, type_params, bases)?;
         let indent = indent();
         let doc = self.doc.trim();
         docstring::write_docstring(f, doc, indent)?;
@@ -248,27 +400,28 @@ impl fmt::Display for ClassDef {
             writeln!(f, "{indent}__match_args__ = ({match_args_txt},)")?;
         }
         for attr in &self.attrs {
-            attr.fmt(f)?;
+            attr.render(f, ctx)?;
         }
         for (getter, setter) in self.getter_setters.values() {
             if let Some(getter) = getter {
-                GetterDisplay(getter).fmt(f)?;
+                GetterDisplay(getter).render(f, ctx)?;
             }
             if let Some(setter) = setter {
-                SetterDisplay(setter).fmt(f)?;
+                SetterDisplay(setter).render(f, ctx)?;
             }
         }
         for methods in self.methods.values() {
             let overloaded = methods.len() > 1;
             for method in methods {
                 if overloaded {
-                    writeln!(f, "{indent}@typing.overload")?;
+                    let overload = ctx.qualified_name(&"typing".into(), "overload");
+                    writeln!(f, "{indent}@{overload}")?;
                 }
-                method.fmt(f)?;
+                method.render(f, ctx)?;
             }
         }
         for class in &self.classes {
-            let emit = format!("{class}");
+            let emit = ctx.display(class).to_string();
             for line in emit.lines() {
                 writeln!(f, "{indent}{line}")?;
             }
@@ -280,3 +433,9 @@ impl fmt::Display for ClassDef {
         Ok(())
     }
 }
+
+impl fmt::Display for ClassDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
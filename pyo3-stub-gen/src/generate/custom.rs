@@ -0,0 +1,280 @@
+//! Hand-written regions kept verbatim when a stub file is regenerated, e.g.
+//!
+//! ```python
+//! class A:
+//!     def generated(self) -> None: ...
+//!     # pyo3-stub-gen: begin custom
+//!     def __getattr__(self, name: str) -> int: ...
+//!     # pyo3-stub-gen: end custom
+//! ```
+//!
+//! A region at the top level is placed at the end of the module,
+//! and a region in a class body is placed at the end of the body of the class with the same name.
+
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+pub(crate) const CUSTOM_BEGIN: &str = "# pyo3-stub-gen: begin custom";
+pub(crate) const CUSTOM_END: &str = "# pyo3-stub-gen: end custom";
+
+/// A hand-written region including the marker lines
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CustomRegion {
+    /// Names of the (nested) classes enclosing the region, empty for the module level
+    pub class: Vec<String>,
+    pub lines: Vec<String>,
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Name of the class defined by the line, e.g. `A` for `class A(B):`
+fn class_name(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix("class ")?;
+    let end = rest.find(['(', ':'])?;
+    Some(rest[..end].trim())
+}
+
+/// Toggle the docstring state by the `r"""` and `"""` lines written by the generator
+fn is_docstring_delimiter(line: &str) -> bool {
+    matches!(line.trim(), r#"r""""# | r#"""""#)
+}
+
+/// Read the custom regions from an existing stub file
+pub(crate) fn parse_custom_regions(stub: &str) -> Result<Vec<CustomRegion>> {
+    let mut regions = Vec::new();
+    // (indent, name) of the classes enclosing the current line
+    let mut classes: Vec<(usize, &str)> = Vec::new();
+    let mut in_docstring = false;
+    let mut lines = stub.lines().enumerate();
+    while let Some((lineno, line)) = lines.next() {
+        if is_docstring_delimiter(line) {
+            in_docstring = !in_docstring;
+            continue;
+        }
+        if in_docstring || line.trim().is_empty() {
+            continue;
+        }
+        let indent = indent_of(line);
+        classes.retain(|(class_indent, _)| *class_indent < indent);
+        if let Some(name) = class_name(line) {
+            classes.push((indent, name));
+            continue;
+        }
+        if line.trim() != CUSTOM_BEGIN {
+            continue;
+        }
+        let mut region = vec![line.to_string()];
+        loop {
+            let Some((_, line)) = lines.next() else {
+                bail!(
+                    "`{CUSTOM_BEGIN}` at line {} is not closed by `{CUSTOM_END}`",
+                    lineno + 1
+                );
+            };
+            region.push(line.to_string());
+            if line.trim() == CUSTOM_END {
+                break;
+            }
+        }
+        regions.push(CustomRegion {
+            class: classes.iter().map(|(_, name)| name.to_string()).collect(),
+            lines: region,
+        });
+    }
+    Ok(regions)
+}
+
+/// Index of the line just after the last non-empty line of the class body
+fn class_body_end(lines: &[&str], class: &[String]) -> Option<usize> {
+    let (mut start, mut end, mut indent) = (0, lines.len(), 0);
+    let mut last = None;
+    for name in class {
+        let mut in_docstring = false;
+        let header = (start..end).find(|&i| {
+            if is_docstring_delimiter(lines[i]) {
+                in_docstring = !in_docstring;
+            }
+            !in_docstring && indent_of(lines[i]) == indent && class_name(lines[i]) == Some(name)
+        })?;
+        start = header + 1;
+        end = (start..end)
+            .find(|&i| !lines[i].trim().is_empty() && indent_of(lines[i]) <= indent)
+            .unwrap_or(end);
+        indent = (start..end)
+            .find(|&i| !lines[i].trim().is_empty())
+            .map(|i| indent_of(lines[i]))?;
+        last = (start..end).rev().find(|&i| !lines[i].trim().is_empty());
+    }
+    Some(last? + 1)
+}
+
+/// Insert the custom regions into the generated stub file
+pub(crate) fn insert_custom_regions(generated: &str, regions: &[CustomRegion]) -> String {
+    if regions.is_empty() {
+        return generated.to_string();
+    }
+    let lines: Vec<&str> = generated.lines().collect();
+    let mut inserts: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    let mut module_level = Vec::new();
+    // Regions of inner classes first, since the body of a class ends at the same line as its last inner class
+    let mut regions: Vec<&CustomRegion> = regions.iter().collect();
+    regions.sort_by_key(|region| std::cmp::Reverse(region.class.len()));
+    for region in regions {
+        if region.class.is_empty() {
+            module_level.extend(region.lines.iter().cloned());
+            continue;
+        }
+        match class_body_end(&lines, &region.class) {
+            Some(index) => inserts
+                .entry(index)
+                .or_default()
+                .extend(region.lines.iter().cloned()),
+            None => {
+                log::warn!(
+                    "Class `{}` of a custom region is not generated anymore. The region is moved to the module level.",
+                    region.class.join(".")
+                );
+                let dedent = region
+                    .lines
+                    .iter()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| indent_of(line))
+                    .min()
+                    .unwrap_or(0);
+                module_level.extend(
+                    region
+                        .lines
+                        .iter()
+                        .map(|line| line.get(dedent..).unwrap_or("").to_string()),
+                );
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        for custom in inserts.get(&i).into_iter().flatten() {
+            out.push_str(custom);
+            out.push('\n');
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    for custom in inserts.get(&lines.len()).into_iter().flatten() {
+        out.push_str(custom);
+        out.push('\n');
+    }
+    if !module_level.is_empty() {
+        if !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+        for custom in module_level {
+            out.push_str(&custom);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_custom_regions() -> Result<()> {
+        let existing = r#"# header
+import typing
+
+class A:
+    r"""
+    class B:
+    """
+    def f(self) -> None: ...
+    class B:
+        def g(self) -> None: ...
+        # pyo3-stub-gen: begin custom
+        x: int
+        # pyo3-stub-gen: end custom
+
+    # pyo3-stub-gen: begin custom
+    def __getattr__(self, name: str) -> int: ...
+    # pyo3-stub-gen: end custom
+
+class Removed:
+    # pyo3-stub-gen: begin custom
+    y: int
+    # pyo3-stub-gen: end custom
+
+def h() -> None: ...
+# pyo3-stub-gen: begin custom
+def __getattr__(name: str) -> typing.Any: ...
+# pyo3-stub-gen: end custom
+"#;
+        let mut regions = parse_custom_regions(existing)?;
+        assert_eq!(
+            regions
+                .iter()
+                .map(|region| region.class.join("."))
+                .collect::<Vec<_>>(),
+            vec!["A.B", "A", "Removed", ""]
+        );
+        // The order in the existing file does not matter for nested classes
+        regions.swap(0, 1);
+
+        let generated = r#"# header
+import typing
+
+class A:
+    r"""
+    class B:
+    """
+    def f(self) -> None: ...
+    def f2(self) -> None: ...
+    class B:
+        def g(self) -> None: ...
+
+def h() -> None: ...
+"#;
+        assert_eq!(
+            insert_custom_regions(generated, &regions),
+            r#"# header
+import typing
+
+class A:
+    r"""
+    class B:
+    """
+    def f(self) -> None: ...
+    def f2(self) -> None: ...
+    class B:
+        def g(self) -> None: ...
+        # pyo3-stub-gen: begin custom
+        x: int
+        # pyo3-stub-gen: end custom
+    # pyo3-stub-gen: begin custom
+    def __getattr__(self, name: str) -> int: ...
+    # pyo3-stub-gen: end custom
+
+def h() -> None: ...
+
+# pyo3-stub-gen: begin custom
+y: int
+# pyo3-stub-gen: end custom
+# pyo3-stub-gen: begin custom
+def __getattr__(name: str) -> typing.Any: ...
+# pyo3-stub-gen: end custom
+"#
+        );
+        // Regenerating does not change the output
+        let rendered = insert_custom_regions(generated, &regions);
+        assert_eq!(
+            insert_custom_regions(generated, &parse_custom_regions(&rendered)?),
+            rendered
+        );
+
+        assert!(parse_custom_regions("# pyo3-stub-gen: begin custom\nx: int\n").is_err());
+        Ok(())
+    }
+}
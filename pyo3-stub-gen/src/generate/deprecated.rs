@@ -1,9 +1,45 @@
-use crate::type_info::DeprecatedInfo;
+use crate::{
+    generate::{PythonVersion, Render, RenderContext},
+    stub_type::ImportRef,
+    type_info::{DeprecatedInfo, IgnoreTarget},
+};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-impl fmt::Display for DeprecatedInfo {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "@typing_extensions.deprecated(")?;
+/// Owned [DeprecatedInfo] rendered as `@typing_extensions.deprecated(...)`,
+/// or `@warnings.deprecated(...)` when targeting Python 3.13 or later
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeprecatedDef {
+    pub since: Option<String>,
+    pub note: Option<String>,
+}
+
+impl From<&DeprecatedInfo> for DeprecatedDef {
+    fn from(info: &DeprecatedInfo) -> Self {
+        Self {
+            since: info.since.map(str::to_string),
+            note: info.note.map(str::to_string),
+        }
+    }
+}
+
+impl DeprecatedDef {
+    /// Module providing `deprecated` for the target Python version
+    fn module(target: PythonVersion) -> &'static str {
+        target
+            .typing_module("deprecated")
+            .expect("deprecated is provided by typing_extensions")
+    }
+
+    pub(crate) fn import(&self, target: PythonVersion) -> ImportRef {
+        Self::module(target).into()
+    }
+}
+
+impl Render for DeprecatedDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        let module = Self::module(ctx.target).into();
+        write!(f, "@{}(", ctx.qualified_name(&module, "deprecated"))?;
         match (&self.since, &self.note) {
             (Some(since), Some(note)) => {
                 write!(f, "\"[Since {since}] {note}\"")?;
@@ -22,3 +58,29 @@ impl fmt::Display for DeprecatedInfo {
         write!(f, ")")
     }
 }
+
+impl fmt::Display for DeprecatedDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
+
+/// Owned [IgnoreTarget]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IgnoreTargetDef {
+    /// Ignore all type checking errors `(# type: ignore)`
+    All,
+    /// Ignore specific type checking rules `(# type: ignore[rule1,rule2])`
+    Specified(Vec<String>),
+}
+
+impl From<&IgnoreTarget> for IgnoreTargetDef {
+    fn from(target: &IgnoreTarget) -> Self {
+        match target {
+            IgnoreTarget::All => Self::All,
+            IgnoreTarget::Specified(rules) => {
+                Self::Specified(rules.iter().map(|rule| rule.to_string()).collect())
+            }
+        }
+    }
+}
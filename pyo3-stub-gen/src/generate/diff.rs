@@ -0,0 +1,1006 @@
+use crate::{generate::*, TypeInfo};
+use anyhow::Result;
+use itertools::Itertools;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Result of [crate::StubInfo::diff], i.e. the changes of the Python API between two [crate::StubInfo]
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ApiDiff {
+    pub changes: Vec<ApiChange>,
+}
+
+/// A change of an item in the Python API
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiChange {
+    pub severity: Severity,
+    pub change: ChangeKind,
+    pub item: ItemKind,
+    /// Qualified name of the item, e.g. `pkg.Class.method`, or `pkg.Class.method(arg)` for an argument
+    pub path: String,
+    /// Description of the change, e.g. `` `int` -> `str` `` for a changed type. Empty for added or removed items.
+    pub detail: String,
+}
+
+/// Whether a change may break existing Python code using the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Breaking,
+    NonBreaking,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ItemKind {
+    Module,
+    Class,
+    Enum,
+    Variant,
+    TypedDict,
+    Key,
+    TypeAlias,
+    TypeVar,
+    Function,
+    Method,
+    Member,
+    Variable,
+    Argument,
+    ReturnType,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ItemKind::Module => write!(f, "module"),
+            ItemKind::Class => write!(f, "class"),
+            ItemKind::Enum => write!(f, "enum"),
+            ItemKind::Variant => write!(f, "variant"),
+            ItemKind::TypedDict => write!(f, "typed-dict"),
+            ItemKind::Key => write!(f, "key"),
+            ItemKind::TypeAlias => write!(f, "type-alias"),
+            ItemKind::TypeVar => write!(f, "type-var"),
+            ItemKind::Function => write!(f, "function"),
+            ItemKind::Method => write!(f, "method"),
+            ItemKind::Member => write!(f, "member"),
+            ItemKind::Variable => write!(f, "variable"),
+            ItemKind::Argument => write!(f, "argument"),
+            ItemKind::ReturnType => write!(f, "return type"),
+        }
+    }
+}
+
+impl fmt::Display for ApiChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} `{}`", self.change, self.item, self.path)?;
+        if !self.detail.is_empty() {
+            write!(f, ": {}", self.detail)?;
+        }
+        Ok(())
+    }
+}
+
+impl ApiDiff {
+    /// `true` if any change may break existing Python code
+    pub fn is_breaking(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes
+            .iter()
+            .filter(|change| change.severity == Severity::Breaking)
+    }
+
+    pub fn non_breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes
+            .iter()
+            .filter(|change| change.severity == Severity::NonBreaking)
+    }
+
+    /// JSON report of the changes, i.e. `{"breaking": bool, "changes": [...]}`
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            breaking: bool,
+            changes: &'a [ApiChange],
+        }
+        Ok(serde_json::to_string_pretty(&Report {
+            breaking: self.is_breaking(),
+            changes: &self.changes,
+        })?)
+    }
+}
+
+impl fmt::Display for ApiDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "No API change.");
+        }
+        for (title, severity) in [
+            ("Breaking changes:", Severity::Breaking),
+            ("Non-breaking changes:", Severity::NonBreaking),
+        ] {
+            let mut changes = self
+                .changes
+                .iter()
+                .filter(|change| change.severity == severity)
+                .peekable();
+            if changes.peek().is_none() {
+                continue;
+            }
+            writeln!(f, "{title}")?;
+            for change in changes {
+                writeln!(f, "  {change}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn diff(old: &BTreeMap<String, Module>, new: &BTreeMap<String, Module>) -> ApiDiff {
+    let mut differ = Differ::default();
+    differ.items(
+        ItemKind::Module,
+        "",
+        by_name(
+            old.iter().map(|(name, module)| (name.as_str(), module)),
+            new.iter().map(|(name, module)| (name.as_str(), module)),
+        ),
+        Differ::module,
+    );
+    ApiDiff {
+        changes: differ.changes,
+    }
+}
+
+/// Pair the items of the old and new API by name
+fn by_name<'a, T: ?Sized + 'a>(
+    old: impl IntoIterator<Item = (&'a str, &'a T)>,
+    new: impl IntoIterator<Item = (&'a str, &'a T)>,
+) -> BTreeMap<&'a str, (Option<&'a T>, Option<&'a T>)> {
+    let mut items: BTreeMap<&str, (Option<&T>, Option<&T>)> = BTreeMap::new();
+    for (name, item) in old {
+        items.entry(name).or_default().0 = Some(item);
+    }
+    for (name, item) in new {
+        items.entry(name).or_default().1 = Some(item);
+    }
+    items
+}
+
+/// Group overloads by name
+fn overloads<'a, C: Callable>(
+    callables: impl IntoIterator<Item = &'a C>,
+) -> BTreeMap<&'a str, Vec<&'a C>> {
+    let mut overloads: BTreeMap<&str, Vec<&C>> = BTreeMap::new();
+    for callable in callables {
+        overloads.entry(callable.name()).or_default().push(callable);
+    }
+    overloads
+}
+
+/// Common part of [FunctionDef] and [MethodDef] to compare signatures
+trait Callable {
+    fn name(&self) -> &str;
+    fn args(&self) -> &[Arg];
+    fn return_type(&self) -> &TypeInfo;
+    fn is_async(&self) -> bool;
+    fn method_type(&self) -> Option<MethodType>;
+
+    /// Signature without the name, e.g. `async (x:builtins.int) -> None`
+    fn signature(&self) -> String {
+        format!(
+            "{}({}) -> {}",
+            if self.is_async() { "async " } else { "" },
+            self.args().iter().join(", "),
+            self.return_type()
+        )
+    }
+}
+
+impl Callable for FunctionDef {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn args(&self) -> &[Arg] {
+        &self.args
+    }
+    fn return_type(&self) -> &TypeInfo {
+        &self.r#return
+    }
+    fn is_async(&self) -> bool {
+        self.is_async
+    }
+    fn method_type(&self) -> Option<MethodType> {
+        None
+    }
+}
+
+impl Callable for MethodDef {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn args(&self) -> &[Arg] {
+        &self.args
+    }
+    fn return_type(&self) -> &TypeInfo {
+        &self.r#return
+    }
+    fn is_async(&self) -> bool {
+        self.is_async
+    }
+    fn method_type(&self) -> Option<MethodType> {
+        Some(self.r#type)
+    }
+}
+
+fn method_type_name(r#type: MethodType) -> &'static str {
+    match r#type {
+        MethodType::Instance => "instance method",
+        MethodType::Static => "static method",
+        MethodType::Class => "class method",
+        MethodType::New => "constructor",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParamKind {
+    /// Positional-only argument at the index, i.e. before `/`
+    PositionalOnly(usize),
+    /// Positional-or-keyword argument at the index
+    Positional(usize),
+    KeywordOnly,
+    VarArgs,
+    VarKeywords,
+}
+
+impl ParamKind {
+    fn position(&self) -> Option<usize> {
+        match self {
+            ParamKind::PositionalOnly(position) | ParamKind::Positional(position) => {
+                Some(*position)
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Param<'a> {
+    name: &'a str,
+    kind: ParamKind,
+    r#type: &'a TypeInfo,
+    default: Option<&'a str>,
+}
+
+/// Parameters keyed by name. `*` marker is consumed to make the following arguments keyword-only,
+/// and `/` marker is consumed to make the preceding arguments positional-only.
+fn params(args: &[Arg]) -> BTreeMap<String, Param<'_>> {
+    let mut params: BTreeMap<String, Param> = BTreeMap::new();
+    let mut position = 0;
+    let mut keyword_only = false;
+    for arg in args {
+        let (name, kind, default) = match &arg.signature {
+            Some(SignatureArgDef::Star) => {
+                keyword_only = true;
+                continue;
+            }
+            Some(SignatureArgDef::Slash) => {
+                for param in params.values_mut() {
+                    if let ParamKind::Positional(position) = param.kind {
+                        param.kind = ParamKind::PositionalOnly(position);
+                    }
+                }
+                continue;
+            }
+            Some(SignatureArgDef::Args | SignatureArgDef::AnnotatedArgs) => {
+                keyword_only = true;
+                (format!("*{}", arg.name), ParamKind::VarArgs, None)
+            }
+            Some(SignatureArgDef::Keywords | SignatureArgDef::AnnotatedKeywords) => {
+                (format!("**{}", arg.name), ParamKind::VarKeywords, None)
+            }
+            signature => {
+                let default = match signature {
+                    Some(SignatureArgDef::Assign { default }) => Some(default.as_str()),
+                    _ => None,
+                };
+                let kind = if keyword_only {
+                    ParamKind::KeywordOnly
+                } else {
+                    position += 1;
+                    ParamKind::Positional(position - 1)
+                };
+                (arg.name.clone(), kind, default)
+            }
+        };
+        params.insert(
+            name,
+            Param {
+                name: &arg.name,
+                kind,
+                r#type: &arg.r#type,
+                default,
+            },
+        );
+    }
+    params
+}
+
+/// Readable and writable attributes of classes and enums
+struct Member<'a> {
+    r#type: &'a TypeInfo,
+    writable: bool,
+}
+
+fn members<'a>(
+    attrs: &'a [MemberDef],
+    getters: impl IntoIterator<Item = &'a MemberDef>,
+    setters: impl IntoIterator<Item = &'a MemberDef>,
+) -> BTreeMap<&'a str, Member<'a>> {
+    let mut members = BTreeMap::new();
+    for attr in attrs.iter().chain(getters) {
+        members.insert(
+            attr.name.as_str(),
+            Member {
+                r#type: &attr.r#type,
+                writable: false,
+            },
+        );
+    }
+    for setter in setters {
+        members
+            .entry(setter.name.as_str())
+            .or_insert(Member {
+                r#type: &setter.r#type,
+                writable: true,
+            })
+            .writable = true;
+    }
+    members
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<ApiChange>,
+}
+
+impl Differ {
+    fn push(
+        &mut self,
+        severity: Severity,
+        change: ChangeKind,
+        item: ItemKind,
+        path: &str,
+        detail: impl Into<String>,
+    ) {
+        self.changes.push(ApiChange {
+            severity,
+            change,
+            item,
+            path: path.to_string(),
+            detail: detail.into(),
+        });
+    }
+
+    fn breaking(&mut self, item: ItemKind, path: &str, detail: impl Into<String>) {
+        self.push(Severity::Breaking, ChangeKind::Changed, item, path, detail);
+    }
+
+    fn non_breaking(&mut self, item: ItemKind, path: &str, detail: impl Into<String>) {
+        self.push(
+            Severity::NonBreaking,
+            ChangeKind::Changed,
+            item,
+            path,
+            detail,
+        );
+    }
+
+    /// Report added and removed items, and compare the items existing in both by `f`
+    fn items<T: ?Sized>(
+        &mut self,
+        item: ItemKind,
+        parent: &str,
+        items: BTreeMap<&str, (Option<&T>, Option<&T>)>,
+        mut f: impl FnMut(&mut Self, &str, &T, &T),
+    ) {
+        for (name, pair) in items {
+            let path = if parent.is_empty() {
+                name.to_string()
+            } else {
+                format!("{parent}.{name}")
+            };
+            match pair {
+                (Some(old), Some(new)) => f(self, &path, old, new),
+                (Some(_), None) => {
+                    self.push(Severity::Breaking, ChangeKind::Removed, item, &path, "")
+                }
+                (None, Some(_)) => {
+                    self.push(Severity::NonBreaking, ChangeKind::Added, item, &path, "")
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn module(&mut self, path: &str, old: &Module, new: &Module) {
+        self.items(
+            ItemKind::Class,
+            path,
+            by_name(
+                old.class.iter().map(|class| (class.name.as_str(), class)),
+                new.class.iter().map(|class| (class.name.as_str(), class)),
+            ),
+            Self::class,
+        );
+        self.items(
+            ItemKind::Enum,
+            path,
+            by_name(
+                old.enum_.iter().map(|enum_| (enum_.name.as_str(), enum_)),
+                new.enum_.iter().map(|enum_| (enum_.name.as_str(), enum_)),
+            ),
+            Self::enum_,
+        );
+        self.items(
+            ItemKind::TypedDict,
+            path,
+            by_name(
+                old.typed_dict.iter().map(|t| (t.name.as_str(), t)),
+                new.typed_dict.iter().map(|t| (t.name.as_str(), t)),
+            ),
+            Self::typed_dict,
+        );
+        self.items(
+            ItemKind::TypeVar,
+            path,
+            by_name(
+                old.type_var.iter().map(|t| (t.name.as_str(), t)),
+                new.type_var.iter().map(|t| (t.name.as_str(), t)),
+            ),
+            |differ, path, old, new| {
+                let (old, new) = (old.to_string(), new.to_string());
+                if old != new {
+                    differ.breaking(
+                        ItemKind::TypeVar,
+                        path,
+                        format!("`{}` -> `{}`", old.trim(), new.trim()),
+                    );
+                }
+            },
+        );
+        self.items(
+            ItemKind::TypeAlias,
+            path,
+            by_name(
+                old.type_alias.iter().map(|t| (t.name.as_str(), t)),
+                new.type_alias.iter().map(|t| (t.name.as_str(), t)),
+            ),
+            |differ, path, old, new| {
+                differ.r#type(ItemKind::TypeAlias, path, &old.r#type, &new.r#type)
+            },
+        );
+        self.items(
+            ItemKind::Function,
+            path,
+            by_name(
+                old.function
+                    .iter()
+                    .map(|(name, functions)| (name.as_str(), functions.as_slice())),
+                new.function
+                    .iter()
+                    .map(|(name, functions)| (name.as_str(), functions.as_slice())),
+            ),
+            |differ, path, old, new| {
+                differ.overloads(
+                    ItemKind::Function,
+                    path,
+                    &old.iter().collect::<Vec<_>>(),
+                    &new.iter().collect::<Vec<_>>(),
+                )
+            },
+        );
+        self.items(
+            ItemKind::Variable,
+            path,
+            by_name(
+                old.variables
+                    .iter()
+                    .map(|(name, variable)| (name.as_str(), variable)),
+                new.variables
+                    .iter()
+                    .map(|(name, variable)| (name.as_str(), variable)),
+            ),
+            |differ, path, old, new| {
+                differ.r#type(ItemKind::Variable, path, &old.type_, &new.type_)
+            },
+        );
+    }
+
+    fn class(&mut self, path: &str, old: &ClassDef, new: &ClassDef) {
+        let old_bases: BTreeSet<String> = old.bases.iter().map(TypeInfo::to_string).collect();
+        let new_bases: BTreeSet<String> = new.bases.iter().map(TypeInfo::to_string).collect();
+        for base in old_bases.difference(&new_bases) {
+            self.breaking(ItemKind::Class, path, format!("base `{base}` is removed"));
+        }
+        for base in new_bases.difference(&old_bases) {
+            self.non_breaking(ItemKind::Class, path, format!("base `{base}` is added"));
+        }
+        let old_params: Vec<String> = old.type_params.iter().map(|t| t.name.clone()).collect();
+        let new_params: Vec<String> = new.type_params.iter().map(|t| t.name.clone()).collect();
+        if old_params != new_params {
+            self.breaking(
+                ItemKind::Class,
+                path,
+                format!(
+                    "type parameters `[{}]` are changed to `[{}]`",
+                    old_params.join(", "),
+                    new_params.join(", ")
+                ),
+            );
+        }
+        self.members(
+            path,
+            members(
+                &old.attrs,
+                old.getter_setters
+                    .values()
+                    .filter_map(|(getter, _)| getter.as_ref()),
+                old.getter_setters
+                    .values()
+                    .filter_map(|(_, setter)| setter.as_ref()),
+            ),
+            members(
+                &new.attrs,
+                new.getter_setters
+                    .values()
+                    .filter_map(|(getter, _)| getter.as_ref()),
+                new.getter_setters
+                    .values()
+                    .filter_map(|(_, setter)| setter.as_ref()),
+            ),
+        );
+        self.methods(
+            path,
+            overloads(old.methods.values().flatten()),
+            overloads(new.methods.values().flatten()),
+        );
+        self.items(
+            ItemKind::Class,
+            path,
+            by_name(
+                old.classes.iter().map(|class| (class.name.as_str(), class)),
+                new.classes.iter().map(|class| (class.name.as_str(), class)),
+            ),
+            Self::class,
+        );
+    }
+
+    fn enum_(&mut self, path: &str, old: &EnumDef, new: &EnumDef) {
+        self.items(
+            ItemKind::Variant,
+            path,
+            by_name(
+                old.variants.iter().map(|(name, _)| (name.as_str(), &())),
+                new.variants.iter().map(|(name, _)| (name.as_str(), &())),
+            ),
+            |_, _, _, _| {},
+        );
+        self.members(
+            path,
+            members(&old.attrs, &old.getters, &old.setters),
+            members(&new.attrs, &new.getters, &new.setters),
+        );
+        self.methods(path, overloads(&old.methods), overloads(&new.methods));
+    }
+
+    fn typed_dict(&mut self, path: &str, old: &TypedDictDef, new: &TypedDictDef) {
+        self.items(
+            ItemKind::Key,
+            path,
+            by_name(
+                old.fields.iter().map(|field| (field.name.as_str(), field)),
+                new.fields.iter().map(|field| (field.name.as_str(), field)),
+            ),
+            |differ, path, old_field, new_field| {
+                differ.r#type(
+                    ItemKind::Key,
+                    path,
+                    &old_field.annotation(old.total),
+                    &new_field.annotation(new.total),
+                )
+            },
+        );
+    }
+
+    fn members(&mut self, path: &str, old: BTreeMap<&str, Member>, new: BTreeMap<&str, Member>) {
+        self.items(
+            ItemKind::Member,
+            path,
+            by_name(
+                old.iter().map(|(k, v)| (*k, v)),
+                new.iter().map(|(k, v)| (*k, v)),
+            ),
+            |differ, path, old, new| {
+                differ.r#type(ItemKind::Member, path, old.r#type, new.r#type);
+                match (old.writable, new.writable) {
+                    (true, false) => differ.breaking(ItemKind::Member, path, "became read-only"),
+                    (false, true) => differ.non_breaking(ItemKind::Member, path, "became writable"),
+                    _ => {}
+                }
+            },
+        );
+    }
+
+    fn methods(
+        &mut self,
+        path: &str,
+        old: BTreeMap<&str, Vec<&MethodDef>>,
+        new: BTreeMap<&str, Vec<&MethodDef>>,
+    ) {
+        self.items(
+            ItemKind::Method,
+            path,
+            by_name(
+                old.iter().map(|(k, v)| (*k, v.as_slice())),
+                new.iter().map(|(k, v)| (*k, v.as_slice())),
+            ),
+            |differ, path, old, new| differ.overloads(ItemKind::Method, path, old, new),
+        );
+    }
+
+    /// Compare the signatures in detail if the function is not overloaded, or the set of overloads otherwise
+    fn overloads<C: Callable>(&mut self, item: ItemKind, path: &str, old: &[&C], new: &[&C]) {
+        if let ([old], [new]) = (old, new) {
+            self.signature(item, path, *old, *new);
+            return;
+        }
+        let old: BTreeSet<String> = old.iter().map(|c| c.signature()).collect();
+        let new: BTreeSet<String> = new.iter().map(|c| c.signature()).collect();
+        for signature in old.difference(&new) {
+            self.breaking(item, path, format!("overload `{signature}` is removed"));
+        }
+        for signature in new.difference(&old) {
+            self.non_breaking(item, path, format!("overload `{signature}` is added"));
+        }
+    }
+
+    fn signature<C: Callable>(&mut self, item: ItemKind, path: &str, old: &C, new: &C) {
+        if let (Some(old), Some(new)) = (old.method_type(), new.method_type()) {
+            if old != new {
+                self.breaking(
+                    item,
+                    path,
+                    format!("{} -> {}", method_type_name(old), method_type_name(new)),
+                );
+            }
+        }
+        match (old.is_async(), new.is_async()) {
+            (false, true) => self.breaking(item, path, "became async"),
+            (true, false) => self.breaking(item, path, "became non-async"),
+            _ => {}
+        }
+
+        let old_params = params(old.args());
+        let new_params = params(new.args());
+        let mut pairs = by_name(
+            old_params.iter().map(|(k, v)| (k.as_str(), v)),
+            new_params.iter().map(|(k, v)| (k.as_str(), v)),
+        );
+        // Positional-only arguments are matched by position, since their names are not a part of the API
+        let renamed: Vec<(&str, &str)> = pairs
+            .iter()
+            .filter_map(|(old_name, pair)| {
+                let (Some(old), None) = pair else {
+                    return None;
+                };
+                let ParamKind::PositionalOnly(position) = old.kind else {
+                    return None;
+                };
+                pairs.iter().find_map(|(new_name, pair)| match pair {
+                    (None, Some(new)) if new.kind == ParamKind::PositionalOnly(position) => {
+                        Some((*old_name, *new_name))
+                    }
+                    _ => None,
+                })
+            })
+            .collect();
+        for (old_name, new_name) in renamed {
+            let (old, _) = pairs.remove(old_name).expect("paired above");
+            pairs.get_mut(new_name).expect("paired above").0 = old;
+        }
+
+        for (name, pair) in pairs {
+            let path = format!("{path}({name})");
+            match pair {
+                (Some(old), Some(new)) => self.param(&path, old, new),
+                (Some(_), None) => self.push(
+                    Severity::Breaking,
+                    ChangeKind::Removed,
+                    ItemKind::Argument,
+                    &path,
+                    "",
+                ),
+                (None, Some(new)) => {
+                    let required = new.default.is_none()
+                        && matches!(
+                            new.kind,
+                            ParamKind::PositionalOnly(_)
+                                | ParamKind::Positional(_)
+                                | ParamKind::KeywordOnly
+                        );
+                    if required {
+                        self.push(
+                            Severity::Breaking,
+                            ChangeKind::Added,
+                            ItemKind::Argument,
+                            &path,
+                            "required",
+                        )
+                    } else {
+                        self.push(
+                            Severity::NonBreaking,
+                            ChangeKind::Added,
+                            ItemKind::Argument,
+                            &path,
+                            "",
+                        )
+                    }
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+
+        self.r#type(
+            ItemKind::ReturnType,
+            path,
+            old.return_type(),
+            new.return_type(),
+        );
+    }
+
+    fn param(&mut self, path: &str, old: &Param, new: &Param) {
+        let item = ItemKind::Argument;
+        if old.name != new.name {
+            self.non_breaking(item, path, format!("renamed from `{}`", old.name));
+        }
+        if let (Some(old), Some(new)) = (old.kind.position(), new.kind.position()) {
+            if old != new {
+                self.breaking(item, path, format!("moved from position {old} to {new}"));
+            }
+        }
+        match (old.kind, new.kind) {
+            (ParamKind::PositionalOnly(_) | ParamKind::Positional(_), ParamKind::KeywordOnly) => {
+                self.breaking(item, path, "became keyword-only")
+            }
+            (ParamKind::Positional(_) | ParamKind::KeywordOnly, ParamKind::PositionalOnly(_)) => {
+                self.breaking(item, path, "became positional-only")
+            }
+            (ParamKind::KeywordOnly, ParamKind::Positional(_)) => {
+                self.non_breaking(item, path, "became positional")
+            }
+            (ParamKind::PositionalOnly(_), ParamKind::Positional(_)) => {
+                self.non_breaking(item, path, "became positional-or-keyword")
+            }
+            _ => {}
+        }
+        self.r#type(item, path, old.r#type, new.r#type);
+        match (old.default, new.default) {
+            (Some(old), None) => self.breaking(item, path, format!("default `{old}` is removed")),
+            (None, Some(new)) => self.non_breaking(item, path, format!("default `{new}` is added")),
+            (Some(old), Some(new)) if old != new => {
+                self.non_breaking(item, path, format!("default `{old}` -> `{new}`"))
+            }
+            _ => {}
+        }
+    }
+
+    /// Type changes are always breaking since subtyping is not checked here
+    fn r#type(&mut self, item: ItemKind, path: &str, old: &TypeInfo, new: &TypeInfo) {
+        let (old, new) = (old.to_string(), new.to_string());
+        if old != new {
+            self.breaking(item, path, format!("`{old}` -> `{new}`"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn arg(name: &str, r#type: TypeInfo, signature: Option<SignatureArgDef>) -> Arg {
+        Arg {
+            name: name.to_string(),
+            r#type,
+            signature,
+        }
+    }
+
+    fn function(name: &str, args: Vec<Arg>, r#return: TypeInfo) -> FunctionDef {
+        FunctionDef {
+            name: name.to_string(),
+            args,
+            r#return,
+            doc: String::new(),
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+        }
+    }
+
+    fn module(functions: Vec<FunctionDef>) -> BTreeMap<String, Module> {
+        let mut module = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        for function in functions {
+            module
+                .function
+                .insert(function.name.clone(), vec![function]);
+        }
+        BTreeMap::from([("pkg".to_string(), module)])
+    }
+
+    #[test]
+    fn test_diff() {
+        let int = TypeInfo::builtin("int");
+        let default = |value: &str| {
+            Some(SignatureArgDef::Assign {
+                default: value.to_string(),
+            })
+        };
+        let old = module(vec![
+            function(
+                "f",
+                vec![
+                    arg("a", int.clone(), None),
+                    arg("b", int.clone(), default("1")),
+                    arg("c", int.clone(), default("2")),
+                ],
+                int.clone(),
+            ),
+            function("removed", vec![], TypeInfo::none()),
+        ]);
+        let new = module(vec![
+            function(
+                "f",
+                vec![
+                    arg("a", TypeInfo::builtin("str"), None),
+                    arg("c", int.clone(), default("3")),
+                    arg("", TypeInfo::any(), Some(SignatureArgDef::Star)),
+                    arg("b", int.clone(), default("1")),
+                    arg("d", int.clone(), default("4")),
+                ],
+                TypeInfo::none(),
+            ),
+            function("added", vec![], TypeInfo::none()),
+        ]);
+
+        let diff = diff(&old, &new);
+        assert_eq!(
+            diff.changes
+                .iter()
+                .map(|change| change.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "added function `pkg.added`",
+                "changed argument `pkg.f(a)`: `builtins.int` -> `builtins.str`",
+                "changed argument `pkg.f(b)`: became keyword-only",
+                "changed argument `pkg.f(c)`: moved from position 2 to 1",
+                "changed argument `pkg.f(c)`: default `2` -> `3`",
+                "added argument `pkg.f(d)`",
+                "changed return type `pkg.f`: `builtins.int` -> `None`",
+                "removed function `pkg.removed`",
+            ]
+        );
+        assert_eq!(
+            diff.changes
+                .iter()
+                .map(|change| change.severity == Severity::Breaking)
+                .collect::<Vec<_>>(),
+            vec![false, true, true, true, false, false, true, true]
+        );
+        assert!(diff.is_breaking());
+        assert!(diff
+            .to_json()
+            .unwrap()
+            .starts_with("{\n  \"breaking\": true,"));
+        assert!(diff
+            .to_string()
+            .starts_with("Breaking changes:\n  changed argument `pkg.f(a)`"));
+        assert!(!super::diff(&old, &old).is_breaking());
+    }
+
+    #[test]
+    fn test_diff_positional_only() {
+        let int = TypeInfo::builtin("int");
+        let slash = || arg("", TypeInfo::any(), Some(SignatureArgDef::Slash));
+        let old = module(vec![
+            function(
+                "renamed",
+                vec![
+                    arg("a", int.clone(), None),
+                    arg("b", int.clone(), None),
+                    slash(),
+                ],
+                TypeInfo::none(),
+            ),
+            function(
+                "relaxed",
+                vec![arg("a", int.clone(), None), slash()],
+                TypeInfo::none(),
+            ),
+            function(
+                "restricted",
+                vec![arg("a", int.clone(), None)],
+                TypeInfo::none(),
+            ),
+        ]);
+        let new = module(vec![
+            function(
+                "renamed",
+                vec![
+                    arg("x", int.clone(), None),
+                    arg("b", int.clone(), None),
+                    slash(),
+                ],
+                TypeInfo::none(),
+            ),
+            function(
+                "relaxed",
+                vec![arg("a", int.clone(), None)],
+                TypeInfo::none(),
+            ),
+            function(
+                "restricted",
+                vec![arg("a", int.clone(), None), slash()],
+                TypeInfo::none(),
+            ),
+        ]);
+
+        let diff = diff(&old, &new);
+        assert_eq!(
+            diff.changes
+                .iter()
+                .map(|change| (change.to_string(), change.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "changed argument `pkg.relaxed(a)`: became positional-or-keyword".to_string(),
+                    Severity::NonBreaking
+                ),
+                (
+                    "changed argument `pkg.renamed(x)`: renamed from `a`".to_string(),
+                    Severity::NonBreaking
+                ),
+                (
+                    "changed argument `pkg.restricted(a)`: became positional-only".to_string(),
+                    Severity::Breaking
+                ),
+            ]
+        );
+    }
+}
@@ -1,24 +1,48 @@
-use crate::{generate::*, type_info::*};
+use crate::{generate::*, stub_type::TypeRef, type_info::*};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Definition of a Python enum.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EnumDef {
-    pub name: &'static str,
-    pub doc: &'static str,
-    pub variants: &'static [(&'static str, &'static str)],
+    pub name: String,
+    pub doc: String,
+    /// Pairs of variant name and its docstring
+    pub variants: Vec<(String, String)>,
     pub methods: Vec<MethodDef>,
     pub attrs: Vec<MemberDef>,
     pub getters: Vec<MemberDef>,
     pub setters: Vec<MemberDef>,
 }
 
+impl Import for EnumDef {
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = HashSet::new();
+        // for `from enum import Enum`
+        import.insert(ImportRef::Type(TypeRef::new(
+            "enum".into(),
+            "Enum".to_string(),
+        )));
+        for method in &self.methods {
+            import.extend(method.import(target));
+        }
+        for member in self.attrs.iter().chain(&self.getters).chain(&self.setters) {
+            import.extend(member.import(target));
+        }
+        import
+    }
+}
+
 impl From<&PyEnumInfo> for EnumDef {
     fn from(info: &PyEnumInfo) -> Self {
         Self {
-            name: info.pyclass_name,
-            doc: info.doc,
-            variants: info.variants,
+            name: info.pyclass_name.to_string(),
+            doc: info.doc.to_string(),
+            variants: info
+                .variants
+                .iter()
+                .map(|(name, doc)| (name.to_string(), doc.to_string()))
+                .collect(),
             methods: Vec::new(),
             attrs: Vec::new(),
             getters: Vec::new(),
@@ -27,12 +51,12 @@ impl From<&PyEnumInfo> for EnumDef {
     }
 }
 
-impl fmt::Display for EnumDef {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Render for EnumDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
         writeln!(f, "class {}(Enum):", self.name)?;
         let indent = indent();
-        docstring::write_docstring(f, self.doc, indent)?;
-        for (variant, variant_doc) in self.variants {
+        docstring::write_docstring(f, &self.doc, indent)?;
+        for (variant, variant_doc) in &self.variants {
             writeln!(f, "{indent}{variant} = ...")?;
             docstring::write_docstring(f, variant_doc, indent)?;
         }
@@ -43,19 +67,25 @@ impl fmt::Display for EnumDef {
         {
             writeln!(f)?;
             for attr in &self.attrs {
-                attr.fmt(f)?;
+                attr.render(f, ctx)?;
             }
             for getter in &self.getters {
-                GetterDisplay(getter).fmt(f)?;
+                GetterDisplay(getter).render(f, ctx)?;
             }
             for setter in &self.setters {
-                SetterDisplay(setter).fmt(f)?;
+                SetterDisplay(setter).render(f, ctx)?;
             }
             for methods in &self.methods {
-                methods.fmt(f)?;
+                methods.render(f, ctx)?;
             }
         }
         writeln!(f)?;
         Ok(())
     }
 }
+
+impl fmt::Display for EnumDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
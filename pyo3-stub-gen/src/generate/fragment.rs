@@ -0,0 +1,882 @@
+//! Companion `.pyi` fragments merged into the generated [Module], configured by `[tool.pyo3-stub-gen.fragments]`.
+//!
+//! Only the subset of the stub syntax usually written by hand is supported,
+//! i.e. imports, classes, functions, methods, properties, and annotated variables.
+//! Other syntax, e.g. PEP 695 type parameter lists, is rejected instead of being dropped.
+//! Imported names used in the annotations are imported in the generated stub file,
+//! and aliased imports, e.g. `import numpy as np`, are expanded to the fully qualified names.
+
+use crate::{
+    generate::*,
+    stub_type::{ImportRef, TypeExpr, TypeRef},
+    TypeInfo,
+};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    path::PathBuf,
+};
+
+/// A definition in a fragment which is already defined in the generated module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FragmentConflict {
+    /// Path of the fragment file
+    pub fragment: PathBuf,
+    pub module: String,
+    /// Name of the definition in the module, e.g. `A.f` for the method `f` of the class `A`
+    pub name: String,
+}
+
+/// Error for conflicting definitions in the fragments, see [FragmentConflict]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FragmentConflictError {
+    pub conflicts: Vec<FragmentConflict>,
+}
+
+impl fmt::Display for FragmentConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Definitions in .pyi fragments conflict with the generated ones:"
+        )?;
+        for conflict in &self.conflicts {
+            write!(
+                f,
+                "\n  `{}` in {} is already defined in `{}`",
+                conflict.name,
+                conflict.fragment.display(),
+                conflict.module
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FragmentConflictError {}
+
+/// Parse a fragment into a [Module] to be merged into `module` by [Module::merge]
+pub(crate) fn parse_fragment(source: &str, module: &Module) -> Result<Module> {
+    let lines = logical_lines(source)?;
+    let mut parser = Parser::default();
+    let mut out = Module {
+        name: module.name.clone(),
+        default_module_name: module.default_module_name.clone(),
+        ..Default::default()
+    };
+    parser.module(&lines, &mut out)?;
+    Ok(out)
+}
+
+/// A line joined with the continuation lines, e.g. arguments split into multiple lines
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+    /// 1-based line number where the logical line starts
+    lineno: usize,
+    indent: usize,
+    /// Text without the indent and comments
+    text: String,
+}
+
+fn logical_lines(source: &str) -> Result<Vec<Line>> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let (mut lineno, mut start) = (1, 1);
+    let mut depth = 0usize;
+    let mut quote: Option<&str> = None;
+    let mut rest = source;
+
+    let mut finish = |current: &mut String, start: usize| {
+        let text = current.trim();
+        if !text.is_empty() {
+            lines.push(Line {
+                lineno: start,
+                indent: current.len() - current.trim_start().len(),
+                text: text.to_string(),
+            });
+        }
+        current.clear();
+    };
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(delim) = quote {
+            if c == '\\' {
+                let escaped: String = rest.chars().take(2).collect();
+                current.push_str(&escaped);
+                rest = &rest[escaped.len()..];
+                continue;
+            }
+            if rest.starts_with(delim) {
+                current.push_str(delim);
+                rest = &rest[delim.len()..];
+                quote = None;
+                continue;
+            }
+            if c == '\n' {
+                if delim.len() == 1 {
+                    bail!("line {lineno}: Unterminated string literal");
+                }
+                lineno += 1;
+            }
+            current.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        match c {
+            '#' => {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                rest = &rest[end..];
+                continue;
+            }
+            '"' | '\'' => {
+                let delim = ["\"\"\"", "'''", "\"", "'"]
+                    .into_iter()
+                    .find(|delim| rest.starts_with(delim))
+                    .unwrap();
+                current.push_str(delim);
+                rest = &rest[delim.len()..];
+                quote = Some(delim);
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '\\' if rest[1..].starts_with('\n') => {
+                current.push(' ');
+                rest = &rest[2..];
+                lineno += 1;
+                continue;
+            }
+            '\n' => {
+                lineno += 1;
+                if depth > 0 {
+                    current.push(' ');
+                } else {
+                    finish(&mut current, start);
+                    start = lineno;
+                }
+                rest = &rest[1..];
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if quote.is_some() {
+        bail!("line {start}: Unterminated string literal");
+    }
+    finish(&mut current, start);
+    Ok(lines)
+}
+
+/// Characters of `s` with the bracket depth and whether it is in a string literal
+fn scan(s: &str) -> Vec<(usize, char, usize, bool)> {
+    let mut out = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escape = false;
+    for (i, c) in s.char_indices() {
+        if let Some(q) = quote {
+            out.push((i, c, depth, true));
+            if escape {
+                escape = false;
+            } else if c == '\\' {
+                escape = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => {
+                quote = Some(c);
+                out.push((i, c, depth, true));
+                continue;
+            }
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        out.push((i, c, depth, false));
+        if matches!(c, '(' | '[' | '{') {
+            depth += 1;
+        }
+    }
+    out
+}
+
+/// Index of the first `target` outside string literals and brackets
+fn find_top(s: &str, target: char) -> Option<usize> {
+    scan(s)
+        .into_iter()
+        .find(|&(_, c, depth, in_string)| c == target && depth == 0 && !in_string)
+        .map(|(i, ..)| i)
+}
+
+/// Split `s` by `sep` outside string literals and brackets, dropping empty parts
+fn split_top(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (i, c, depth, in_string) in scan(s) {
+        if c == sep && depth == 0 && !in_string {
+            parts.push(s[start..i].trim());
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(s[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Index of the bracket closing the one at `open`
+fn closing_bracket(s: &str, open: usize) -> Option<usize> {
+    scan(&s[open..])
+        .into_iter()
+        .skip(1)
+        .find(|&(_, c, depth, in_string)| matches!(c, ')' | ']' | '}') && depth == 0 && !in_string)
+        .map(|(i, ..)| open + i)
+}
+
+/// Content of a string literal, e.g. a docstring
+fn string_literal(text: &str) -> Option<String> {
+    let text = text.trim_start_matches(['r', 'R', 'u', 'U']);
+    let delim = ["\"\"\"", "'''", "\"", "'"]
+        .into_iter()
+        .find(|delim| text.starts_with(delim))?;
+    let content = text.strip_prefix(delim)?.strip_suffix(delim)?;
+    // Dedent the continuation lines
+    let mut lines = content.lines();
+    let first = lines.next().unwrap_or_default();
+    let rest: Vec<&str> = lines.collect();
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut out = first.to_string();
+    for line in rest {
+        out.push('\n');
+        out.push_str(line.get(indent..).unwrap_or_default());
+    }
+    Some(out.trim().to_string())
+}
+
+/// Index just after the indented body of `lines[i]`
+fn block_end(lines: &[Line], i: usize) -> usize {
+    (i + 1..lines.len())
+        .find(|&j| lines[j].indent <= lines[i].indent)
+        .unwrap_or(lines.len())
+}
+
+/// Split `name as alias`
+fn split_as(item: &str) -> (&str, Option<&str>) {
+    match item.split_once(" as ") {
+        Some((name, alias)) => (name.trim(), Some(alias.trim())),
+        None => (item.trim(), None),
+    }
+}
+
+/// Function or method definition before applying the decorators
+struct Def {
+    name: String,
+    args: Vec<Arg>,
+    r#return: TypeInfo,
+    doc: String,
+    is_async: bool,
+}
+
+#[derive(Default)]
+struct Parser {
+    /// Names bound by the imports, with the fully qualified name to rewrite for aliases, and the imports to add
+    bindings: BTreeMap<String, (Option<String>, Vec<ImportRef>)>,
+}
+
+impl Parser {
+    fn import(&mut self, text: &str) -> Result<()> {
+        if let Some(rest) = text.strip_prefix("import ") {
+            for item in split_top(rest, ',') {
+                let (module, alias) = split_as(item);
+                match alias {
+                    Some(alias) => {
+                        self.bindings.insert(
+                            alias.to_string(),
+                            (Some(module.to_string()), vec![module.into()]),
+                        );
+                    }
+                    None => {
+                        let head = module.split('.').next().unwrap_or(module);
+                        self.bindings
+                            .entry(head.to_string())
+                            .or_insert((None, Vec::new()))
+                            .1
+                            .push(module.into());
+                    }
+                }
+            }
+            return Ok(());
+        }
+        let rest = text.strip_prefix("from ").context("Invalid import")?;
+        let (module, names) = rest.split_once(" import ").context("Invalid import")?;
+        let module = module.trim();
+        if module.starts_with('.') {
+            bail!("Relative import `{text}` is not supported, use the absolute module name");
+        }
+        if module == "__future__" {
+            return Ok(());
+        }
+        let names = names.trim();
+        let names = names
+            .strip_prefix('(')
+            .and_then(|names| names.strip_suffix(')'))
+            .unwrap_or(names);
+        for item in split_top(names, ',') {
+            let (name, alias) = split_as(item);
+            if name == "*" {
+                bail!("Wildcard import `{text}` is not supported");
+            }
+            match alias {
+                Some(alias) => self.bindings.insert(
+                    alias.to_string(),
+                    (Some(format!("{module}.{name}")), vec![module.into()]),
+                ),
+                None => self.bindings.insert(
+                    name.to_string(),
+                    (
+                        None,
+                        vec![ImportRef::Type(TypeRef::new(
+                            module.into(),
+                            name.to_string(),
+                        ))],
+                    ),
+                ),
+            };
+        }
+        Ok(())
+    }
+
+    /// [TypeInfo] of an annotation with the imports of the names used in it
+    fn r#type(&self, annotation: &str) -> TypeInfo {
+        let mut name = String::new();
+        let mut import = HashSet::new();
+        let chars = scan(annotation);
+        let mut k = 0;
+        while k < chars.len() {
+            let (i, c, _, in_string) = chars[k];
+            if in_string || !(c.is_alphabetic() || c == '_') {
+                name.push(c);
+                k += 1;
+                continue;
+            }
+            let end = chars[k..]
+                .iter()
+                .find(|(_, c, ..)| !(c.is_alphanumeric() || *c == '_' || *c == '.'))
+                .map(|(j, ..)| *j)
+                .unwrap_or(annotation.len());
+            let dotted = &annotation[i..end];
+            let (head, tail) = match dotted.find('.') {
+                Some(dot) => dotted.split_at(dot),
+                None => (dotted, ""),
+            };
+            match self.bindings.get(head) {
+                Some((rewrite, imports)) => {
+                    name.push_str(rewrite.as_deref().unwrap_or(head));
+                    name.push_str(tail);
+                    import.extend(imports.iter().cloned());
+                }
+                None => name.push_str(dotted),
+            }
+            k += dotted.chars().count();
+        }
+        TypeInfo {
+            name: TypeExpr::Raw(name),
+            import,
+        }
+    }
+
+    fn module(&mut self, lines: &[Line], out: &mut Module) -> Result<()> {
+        let mut decorators = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let line = &lines[i];
+            let end = block_end(lines, i);
+            let body = &lines[i + 1..end];
+            let text = line.text.as_str();
+            let result = (|| {
+                if let Some(decorator) = text.strip_prefix('@') {
+                    decorators.push(decorator.trim().to_string());
+                    return Ok(());
+                }
+                if is_def(text) {
+                    let def = self.def(text, body)?;
+                    let function = function(def, std::mem::take(&mut decorators))?;
+                    out.function
+                        .entry(function.name.clone())
+                        .or_default()
+                        .push(function);
+                    return Ok(());
+                }
+                if !decorators.is_empty() {
+                    bail!("Decorator is only supported for functions and methods");
+                }
+                if text.starts_with("class ") {
+                    out.class.push(self.class(text, body)?);
+                } else if text.starts_with("import ") || text.starts_with("from ") {
+                    self.import(text)?;
+                } else if is_skipped(text) {
+                } else if let Some((name, r#type, default)) = self.variable(text) {
+                    out.variables.insert(
+                        name.clone(),
+                        VariableDef {
+                            name,
+                            type_: r#type,
+                            default,
+                        },
+                    );
+                } else {
+                    bail!("Unsupported statement `{text}`");
+                }
+                Ok(())
+            })();
+            result.with_context(|| format!("line {}", line.lineno))?;
+            i = end;
+        }
+        Ok(())
+    }
+
+    fn class(&mut self, header: &str, body: &[Line]) -> Result<ClassDef> {
+        let header = header.strip_prefix("class ").unwrap_or(header);
+        let colon = find_top(header, ':').context("Missing `:` in class definition")?;
+        let (name, bases) = match header[..colon].find('(') {
+            Some(open) => {
+                let close = closing_bracket(header, open).context("Unclosed `(`")?;
+                (&header[..open], split_top(&header[open + 1..close], ','))
+            }
+            None => (&header[..colon], Vec::new()),
+        };
+        let name = name.trim();
+        if !is_identifier(name) {
+            bail!("Unsupported class name `{name}`, use `typing.Generic` instead of a type parameter list");
+        }
+        let mut class = ClassDef {
+            name: name.to_string(),
+            doc: String::new(),
+            attrs: Vec::new(),
+            getter_setters: IndexMap::new(),
+            methods: IndexMap::new(),
+            bases: Vec::new(),
+            type_params: Vec::new(),
+            classes: Vec::new(),
+            match_args: None,
+        };
+        for base in bases {
+            if base.contains('=') {
+                bail!("Keyword argument `{base}` in class definition is not supported");
+            }
+            class.bases.push(self.r#type(base));
+        }
+
+        let mut decorators = Vec::new();
+        let mut i = 0;
+        while i < body.len() {
+            let line = &body[i];
+            let end = block_end(body, i);
+            let inner = &body[i + 1..end];
+            let text = line.text.as_str();
+            let result = (|| {
+                if let Some(decorator) = text.strip_prefix('@') {
+                    decorators.push(decorator.trim().to_string());
+                    return Ok(());
+                }
+                if is_def(text) {
+                    let def = self.def(text, inner)?;
+                    method(&mut class, def, std::mem::take(&mut decorators))?;
+                    return Ok(());
+                }
+                if !decorators.is_empty() {
+                    bail!("Decorator is only supported for functions and methods");
+                }
+                if text.starts_with("class ") {
+                    class.classes.push(self.class(text, inner)?);
+                } else if i == 0 && string_literal(text).is_some() {
+                    class.doc = string_literal(text).unwrap_or_default();
+                } else if is_skipped(text) {
+                } else if let Some((name, r#type, default)) = self.variable(text) {
+                    class.attrs.push(MemberDef {
+                        name,
+                        r#type,
+                        doc: String::new(),
+                        default,
+                        deprecated: None,
+                    });
+                } else {
+                    bail!("Unsupported statement `{text}`");
+                }
+                Ok(())
+            })();
+            result.with_context(|| format!("line {}", line.lineno))?;
+            i = end;
+        }
+        Ok(class)
+    }
+
+    fn def(&self, header: &str, body: &[Line]) -> Result<Def> {
+        let (is_async, header) = match header.strip_prefix("async ") {
+            Some(header) => (true, header.trim_start()),
+            None => (false, header),
+        };
+        let header = header.strip_prefix("def ").unwrap_or(header);
+        let open = header
+            .find('(')
+            .context("Missing `(` in function definition")?;
+        let close = closing_bracket(header, open).context("Unclosed `(`")?;
+        let rest = header[close + 1..].trim();
+        let colon = find_top(rest, ':').context("Missing `:` in function definition")?;
+        let r#return = match rest[..colon].trim().strip_prefix("->") {
+            Some(annotation) => self.r#type(annotation.trim()),
+            None => TypeInfo::any(),
+        };
+
+        let mut args = Vec::new();
+        for param in split_top(&header[open + 1..close], ',') {
+            if param == "/" || param == "*" {
+                let signature = if param == "/" {
+                    SignatureArgDef::Slash
+                } else {
+                    SignatureArgDef::Star
+                };
+                args.push(Arg {
+                    name: String::new(),
+                    r#type: TypeInfo::none(),
+                    signature: Some(signature),
+                });
+                continue;
+            }
+            let (param, default) = match find_top(param, '=') {
+                Some(eq) => (param[..eq].trim(), Some(param[eq + 1..].trim().to_string())),
+                None => (param, None),
+            };
+            let (name, r#type) = match find_top(param, ':') {
+                Some(colon) => (
+                    param[..colon].trim(),
+                    Some(self.r#type(param[colon + 1..].trim())),
+                ),
+                None => (param, None),
+            };
+            let (name, signature) = if let Some(name) = name.strip_prefix("**") {
+                match r#type {
+                    Some(_) => (name, SignatureArgDef::AnnotatedKeywords),
+                    None => (name, SignatureArgDef::Keywords),
+                }
+            } else if let Some(name) = name.strip_prefix('*') {
+                match r#type {
+                    Some(_) => (name, SignatureArgDef::AnnotatedArgs),
+                    None => (name, SignatureArgDef::Args),
+                }
+            } else if let Some(default) = default {
+                (name, SignatureArgDef::Assign { default })
+            } else {
+                (name, SignatureArgDef::Ident)
+            };
+            if !is_identifier(name) {
+                bail!("Unsupported argument `{param}`");
+            }
+            args.push(Arg {
+                name: name.to_string(),
+                r#type: r#type.unwrap_or_else(TypeInfo::any),
+                signature: Some(signature),
+            });
+        }
+
+        let doc = body
+            .first()
+            .and_then(|line| string_literal(&line.text))
+            .unwrap_or_default();
+        let name = header[..open].trim();
+        if !is_identifier(name) {
+            bail!("Unsupported function name `{name}`, type parameter lists are not supported");
+        }
+        Ok(Def {
+            name: name.to_string(),
+            args,
+            r#return,
+            doc,
+            is_async,
+        })
+    }
+
+    /// Annotated variable `name: type` or `name: type = default`
+    fn variable(&self, text: &str) -> Option<(String, TypeInfo, Option<String>)> {
+        let colon = find_top(text, ':')?;
+        let name = text[..colon].trim();
+        if !is_identifier(name) {
+            return None;
+        }
+        let rest = &text[colon + 1..];
+        let (annotation, default) = match find_top(rest, '=') {
+            Some(eq) => (&rest[..eq], Some(rest[eq + 1..].trim().to_string())),
+            None => (rest, None),
+        };
+        Some((name.to_string(), self.r#type(annotation.trim()), default))
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_numeric())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn is_def(text: &str) -> bool {
+    text.starts_with("def ") || text.starts_with("async def ")
+}
+
+fn is_skipped(text: &str) -> bool {
+    matches!(text, "..." | "pass") || string_literal(text).is_some()
+}
+
+/// Name of a decorator without the arguments, e.g. `typing.overload`
+fn decorator_name(decorator: &str) -> &str {
+    decorator
+        .split_once('(')
+        .map_or(decorator, |(name, _)| name)
+        .trim()
+}
+
+fn is_overload(decorator: &str) -> bool {
+    matches!(
+        decorator_name(decorator),
+        "overload" | "typing.overload" | "typing_extensions.overload"
+    )
+}
+
+/// `@deprecated("note")` of `typing_extensions`, `warnings`, or imported directly
+fn deprecated(decorator: &str) -> Option<DeprecatedDef> {
+    let name = decorator_name(decorator);
+    if name != "deprecated" && !name.ends_with(".deprecated") {
+        return None;
+    }
+    let note = decorator
+        .split_once('(')
+        .and_then(|(_, args)| args.trim_end().strip_suffix(')'))
+        .and_then(string_literal)
+        .filter(|note| !note.is_empty());
+    Some(DeprecatedDef { since: None, note })
+}
+
+fn function(def: Def, decorators: Vec<String>) -> Result<FunctionDef> {
+    let mut function = FunctionDef {
+        name: def.name,
+        args: def.args,
+        r#return: def.r#return,
+        doc: def.doc,
+        is_async: def.is_async,
+        deprecated: None,
+        type_ignored: None,
+    };
+    for decorator in decorators {
+        if let Some(deprecated) = deprecated(&decorator) {
+            function.deprecated = Some(deprecated);
+        } else if !is_overload(&decorator) {
+            bail!("Unsupported decorator `@{decorator}` for function");
+        }
+    }
+    Ok(function)
+}
+
+/// Add a method, property getter, or setter to the class
+fn method(class: &mut ClassDef, mut def: Def, decorators: Vec<String>) -> Result<()> {
+    let mut r#type = if def.name == "__new__" {
+        MethodType::New
+    } else {
+        MethodType::Instance
+    };
+    let mut deprecated_ = None;
+    let mut property = None;
+    for decorator in &decorators {
+        match decorator_name(decorator) {
+            "staticmethod" => r#type = MethodType::Static,
+            "classmethod" => r#type = MethodType::Class,
+            "property" => property = Some(false),
+            name if name == format!("{}.setter", def.name) => property = Some(true),
+            _ => {
+                if let Some(deprecated) = deprecated(decorator) {
+                    deprecated_ = Some(deprecated);
+                } else if !is_overload(decorator) {
+                    bail!("Unsupported decorator `@{decorator}` for method");
+                }
+            }
+        }
+    }
+    if r#type != MethodType::Static {
+        if def.args.is_empty() {
+            bail!("Missing `self` or `cls` argument of `{}`", def.name);
+        }
+        def.args.remove(0);
+    }
+
+    if let Some(is_setter) = property {
+        let r#type = if is_setter {
+            def.args
+                .first()
+                .map(|arg| arg.r#type.clone())
+                .context("Missing value argument of setter")?
+        } else {
+            def.r#return
+        };
+        let member = MemberDef {
+            name: def.name.clone(),
+            r#type,
+            doc: def.doc,
+            default: None,
+            deprecated: deprecated_,
+        };
+        let entry = class.getter_setters.entry(def.name).or_default();
+        if is_setter {
+            entry.1 = Some(member);
+        } else {
+            entry.0 = Some(member);
+        }
+        return Ok(());
+    }
+
+    class
+        .methods
+        .entry(def.name.clone())
+        .or_default()
+        .push(MethodDef {
+            name: def.name,
+            args: def.args,
+            r#return: def.r#return,
+            doc: def.doc,
+            r#type,
+            is_async: def.is_async,
+            deprecated: deprecated_,
+            type_ignored: None,
+        });
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_fragment() -> Result<()> {
+        let module = Module {
+            name: "pkg._native".to_string(),
+            default_module_name: "pkg._native".to_string(),
+            ..Default::default()
+        };
+        let fragment = parse_fragment(
+            r#"
+import numpy as np
+import typing
+from collections.abc import Sequence
+
+# comment
+VERSION: str
+
+class A(typing.Generic[T]):
+    """
+    Doc of A
+    """
+    x: int = 1
+
+    @property
+    def y(self) -> np.ndarray: ...
+    @y.setter
+    def y(self, value: np.ndarray) -> None: ...
+
+    @staticmethod
+    def new(
+        values: Sequence[int],  # comment in arguments
+        *,
+        scale: float = 1.0,
+    ) -> "A":
+        r"""Create A"""
+        ...
+
+@typing.overload
+def f(x: int) -> int: ...
+@typing.overload
+async def f(x: str, *args: int, **kwargs: str) -> str: ...
+"#,
+            &module,
+        )?;
+
+        assert_eq!(fragment.variables["VERSION"].type_.to_string(), "str");
+
+        let class = &fragment.class[0];
+        assert_eq!(class.name, "A");
+        assert_eq!(class.doc, "Doc of A");
+        assert_eq!(class.bases[0].to_string(), "typing.Generic[T]");
+        assert_eq!(class.attrs[0].default.as_deref(), Some("1"));
+        let (getter, setter) = &class.getter_setters["y"];
+        assert_eq!(getter.as_ref().unwrap().r#type.to_string(), "numpy.ndarray");
+        assert_eq!(setter.as_ref().unwrap().r#type.to_string(), "numpy.ndarray");
+        assert!(getter
+            .as_ref()
+            .unwrap()
+            .r#type
+            .import
+            .contains(&ImportRef::from("numpy")));
+
+        let new = &class.methods["new"][0];
+        assert_eq!(new.r#type, MethodType::Static);
+        assert_eq!(new.doc, "Create A");
+        assert_eq!(
+            new.args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>(),
+            vec!["values:Sequence[int]", "*", "scale:float=1.0"]
+        );
+        assert!(new.args[0]
+            .r#type
+            .import
+            .contains(&ImportRef::Type(TypeRef::new(
+                "collections.abc".into(),
+                "Sequence".to_string()
+            ))));
+        assert_eq!(new.r#return.to_string(), "\"A\"");
+
+        let f = &fragment.function["f"];
+        assert_eq!(f.len(), 2);
+        assert!(f[1].is_async);
+        assert_eq!(
+            f[1].args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>(),
+            vec!["x:str", "*args:int", "**kwargs:str"]
+        );
+
+        assert!(parse_fragment("x = 1\n", &module).is_err());
+        assert!(parse_fragment("from . import x\n", &module).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_fragment_signature() -> Result<()> {
+        let module = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        let fragment = parse_fragment(
+            "def f(x: int, /, y: int, *args: str, z: int = 0, **kwargs) -> None: ...\n",
+            &module,
+        )?;
+        assert_eq!(
+            fragment.function["f"][0].to_string(),
+            "def f(x:int, /, y:int, *args:str, z:int=0, **kwargs) -> None: ...\n\n"
+        );
+
+        // Type parameter lists are rejected instead of being parsed as a part of the name
+        assert!(parse_fragment("class C[T]:\n    x: T\n", &module).is_err());
+        assert!(parse_fragment("def f[T](x: T) -> T: ...\n", &module).is_err());
+        assert!(parse_fragment("def f(x, (y, z)) -> None: ...\n", &module).is_err());
+        Ok(())
+    }
+}
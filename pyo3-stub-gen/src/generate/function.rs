@@ -1,29 +1,29 @@
 use crate::stub_type::ImportRef;
 use crate::{generate::*, rule_name::RuleName, type_info::*, TypeInfo};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Definition of a Python function.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionDef {
-    pub name: &'static str,
+    pub name: String,
     pub args: Vec<Arg>,
     pub r#return: TypeInfo,
-    pub doc: &'static str,
+    pub doc: String,
     pub is_async: bool,
-    pub deprecated: Option<DeprecatedInfo>,
-    pub type_ignored: Option<IgnoreTarget>,
+    pub deprecated: Option<DeprecatedDef>,
+    pub type_ignored: Option<IgnoreTargetDef>,
 }
 
 impl Import for FunctionDef {
-    fn import(&self) -> HashSet<ImportRef> {
-        let mut import = self.r#return.import.clone();
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = self.r#return.import(target);
         for arg in &self.args {
-            import.extend(arg.import().into_iter());
+            import.extend(arg.import(target));
         }
-        // Add typing_extensions import if deprecated
-        if self.deprecated.is_some() {
-            import.insert("typing_extensions".into());
+        if let Some(deprecated) = &self.deprecated {
+            import.insert(deprecated.import(target));
         }
         import
     }
@@ -32,39 +32,39 @@ impl Import for FunctionDef {
 impl From<&PyFunctionInfo> for FunctionDef {
     fn from(info: &PyFunctionInfo) -> Self {
         Self {
-            name: info.name,
+            name: info.name.to_string(),
             args: info.args.iter().map(Arg::from).collect(),
             r#return: (info.r#return)(),
-            doc: info.doc,
+            doc: info.doc.to_string(),
             is_async: info.is_async,
-            deprecated: info.deprecated.clone(),
-            type_ignored: info.type_ignored,
+            deprecated: info.deprecated.as_ref().map(DeprecatedDef::from),
+            type_ignored: info.type_ignored.as_ref().map(IgnoreTargetDef::from),
         }
     }
 }
 
-impl fmt::Display for FunctionDef {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Render for FunctionDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
         // Add deprecated decorator if present
         if let Some(deprecated) = &self.deprecated {
-            writeln!(f, "{deprecated}")?;
+            writeln!(f, "{}", ctx.display(deprecated))?;
         }
 
         let async_ = if self.is_async { "async " } else { "" };
         write!(f, "{async_}def {}(", self.name)?;
         for (i, arg) in self.args.iter().enumerate() {
-            write!(f, "{arg}")?;
+            write!(f, "{}", ctx.display(arg))?;
             if i != self.args.len() - 1 {
                 write!(f, ", ")?;
             }
         }
-        write!(f, ") -> {}:", self.r#return)?;
+        write!(f, ") -> {}:", ctx.display(&self.r#return))?;
 
         // Calculate type: ignore comment once
         let type_ignore_comment = if let Some(target) = &self.type_ignored {
             match target {
-                IgnoreTarget::All => Some("  # type: ignore".to_string()),
-                IgnoreTarget::Specified(rules) => {
+                IgnoreTargetDef::All => Some("  # type: ignore".to_string()),
+                IgnoreTargetDef::Specified(rules) => {
                     let rules_str = rules
                         .iter()
                         .map(|r| {
@@ -82,14 +82,14 @@ impl fmt::Display for FunctionDef {
             None
         };
 
-        let doc = self.doc;
+        let doc = &self.doc;
         if !doc.is_empty() {
             // Add type: ignore comment for functions with docstrings
             if let Some(comment) = &type_ignore_comment {
                 write!(f, "{comment}")?;
             }
             writeln!(f)?;
-            docstring::write_docstring(f, self.doc, indent())?;
+            docstring::write_docstring(f, &self.doc, indent())?;
         } else {
             write!(f, " ...")?;
             // Add type: ignore comment for functions without docstrings
@@ -102,3 +102,9 @@ impl fmt::Display for FunctionDef {
         Ok(())
     }
 }
+
+impl fmt::Display for FunctionDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
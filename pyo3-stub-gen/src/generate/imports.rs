@@ -0,0 +1,621 @@
+//! Import statements of a stub file.
+//!
+//! The imports of every item in a [Module] are resolved at once before printing it:
+//!
+//! - They are grouped isort-style, i.e. standard library, third-party, and first-party sections separated by a blank line.
+//! - With [TypeNameStyle::Short], the names used as `module.name` are imported by `from module import name`
+//!   and printed by the short names unless it is ambiguous.
+//! - A module shadowed by a name defined in the stub file, e.g. a class named `typing`, is imported with an alias
+//!   like `import typing as _typing`, and the type names are printed with the alias while the module is printed.
+
+use crate::{
+    generate::*,
+    stub_type::{ImportRef, ModuleRef, TypeRef},
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Top-level modules of the Python standard library used to group the imports isort-style.
+///
+/// This is the union of `sys.stdlib_module_names` of CPython 3.9 to 3.14 except the private ones starting with `_`,
+/// so that the modules removed by PEP 594 are still grouped as the standard library.
+/// Update it for a new Python version by
+/// `python -c 'import sys; print(sorted(m for m in sys.stdlib_module_names if not m.startswith("_")))'`.
+/// A module missing here is only grouped as third-party, which does not change the meaning of the stub file.
+const STDLIB: &[&str] = &[
+    "abc",
+    "aifc",
+    "annotationlib",
+    "argparse",
+    "array",
+    "ast",
+    "asynchat",
+    "asyncio",
+    "asyncore",
+    "atexit",
+    "audioop",
+    "base64",
+    "bdb",
+    "binascii",
+    "bisect",
+    "builtins",
+    "bz2",
+    "cProfile",
+    "calendar",
+    "cgi",
+    "cgitb",
+    "chunk",
+    "cmath",
+    "cmd",
+    "code",
+    "codecs",
+    "codeop",
+    "collections",
+    "colorsys",
+    "compileall",
+    "compression",
+    "concurrent",
+    "configparser",
+    "contextlib",
+    "contextvars",
+    "copy",
+    "copyreg",
+    "crypt",
+    "csv",
+    "ctypes",
+    "curses",
+    "dataclasses",
+    "datetime",
+    "dbm",
+    "decimal",
+    "difflib",
+    "dis",
+    "distutils",
+    "doctest",
+    "email",
+    "encodings",
+    "ensurepip",
+    "enum",
+    "errno",
+    "faulthandler",
+    "fcntl",
+    "filecmp",
+    "fileinput",
+    "fnmatch",
+    "fractions",
+    "ftplib",
+    "functools",
+    "gc",
+    "genericpath",
+    "getopt",
+    "getpass",
+    "gettext",
+    "glob",
+    "graphlib",
+    "grp",
+    "gzip",
+    "hashlib",
+    "heapq",
+    "hmac",
+    "html",
+    "http",
+    "idlelib",
+    "imaplib",
+    "imghdr",
+    "imp",
+    "importlib",
+    "inspect",
+    "io",
+    "ipaddress",
+    "itertools",
+    "json",
+    "keyword",
+    "lib2to3",
+    "linecache",
+    "locale",
+    "logging",
+    "lzma",
+    "mailbox",
+    "mailcap",
+    "marshal",
+    "math",
+    "mimetypes",
+    "mmap",
+    "modulefinder",
+    "msilib",
+    "msvcrt",
+    "multiprocessing",
+    "netrc",
+    "nis",
+    "nntplib",
+    "nt",
+    "ntpath",
+    "nturl2path",
+    "numbers",
+    "opcode",
+    "operator",
+    "optparse",
+    "os",
+    "ossaudiodev",
+    "pathlib",
+    "pdb",
+    "pickle",
+    "pickletools",
+    "pipes",
+    "pkgutil",
+    "platform",
+    "plistlib",
+    "poplib",
+    "posix",
+    "posixpath",
+    "pprint",
+    "profile",
+    "pstats",
+    "pty",
+    "pwd",
+    "py_compile",
+    "pyclbr",
+    "pydoc",
+    "pydoc_data",
+    "pyexpat",
+    "queue",
+    "quopri",
+    "random",
+    "re",
+    "readline",
+    "reprlib",
+    "resource",
+    "rlcompleter",
+    "runpy",
+    "sched",
+    "secrets",
+    "select",
+    "selectors",
+    "shelve",
+    "shlex",
+    "shutil",
+    "signal",
+    "site",
+    "smtpd",
+    "smtplib",
+    "sndhdr",
+    "socket",
+    "socketserver",
+    "spwd",
+    "sqlite3",
+    "sre_compile",
+    "sre_constants",
+    "sre_parse",
+    "ssl",
+    "stat",
+    "statistics",
+    "string",
+    "stringprep",
+    "struct",
+    "subprocess",
+    "sunau",
+    "symtable",
+    "sys",
+    "sysconfig",
+    "syslog",
+    "tabnanny",
+    "tarfile",
+    "telnetlib",
+    "tempfile",
+    "termios",
+    "textwrap",
+    "threading",
+    "time",
+    "timeit",
+    "tkinter",
+    "token",
+    "tokenize",
+    "tomllib",
+    "trace",
+    "traceback",
+    "tracemalloc",
+    "tty",
+    "turtle",
+    "turtledemo",
+    "types",
+    "typing",
+    "unicodedata",
+    "unittest",
+    "urllib",
+    "uu",
+    "uuid",
+    "venv",
+    "warnings",
+    "wave",
+    "weakref",
+    "webbrowser",
+    "winreg",
+    "winsound",
+    "wsgiref",
+    "xdrlib",
+    "xml",
+    "xmlrpc",
+    "zipapp",
+    "zipfile",
+    "zipimport",
+    "zlib",
+    "zoneinfo",
+];
+
+/// How the type names are printed in the stub files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeNameStyle {
+    /// Fully qualified names with `import module`, e.g. `typing.Sequence[builtins.int]`
+    #[default]
+    Qualified,
+    /// Short names with `from module import name`, e.g. `Sequence[int]`.
+    /// `builtins.` is dropped without import.
+    /// A name stays fully qualified if the short name is ambiguous,
+    /// e.g. it is defined in the stub file, or used in two modules like `typing.Sequence` and `collections.abc.Sequence`.
+    Short,
+}
+
+/// isort sections in the printed order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Section {
+    StandardLibrary,
+    ThirdParty,
+    FirstParty,
+}
+
+/// Resolved import statements of a [Module]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Imports {
+    /// `import module` or `import module as alias`
+    modules: BTreeMap<String, Option<String>>,
+    /// `from module import name, ...`
+    from: BTreeMap<String, BTreeSet<String>>,
+    /// `from . import submodule`
+    relative: BTreeSet<String>,
+    /// `(module, name)` printed by the short name
+    short: BTreeSet<(String, String)>,
+    /// Top-level package of the PyO3 module, which is imported in the first-party section
+    first_party: String,
+    default_module_name: String,
+}
+
+impl Imports {
+    pub(crate) fn resolve(module: &Module, target: PythonVersion) -> Self {
+        let mut imports = Imports {
+            relative: module.submodules.clone(),
+            first_party: top_level(&module.default_module_name).to_string(),
+            default_module_name: module.default_module_name.clone(),
+            ..Default::default()
+        };
+        let mut qualified = BTreeSet::new();
+        // Modules used in the type expressions written as is, e.g. `TypeExpr::Raw`
+        let mut raw = BTreeSet::new();
+        for import_ref in module.import(target) {
+            match import_ref {
+                ImportRef::Module(module_ref) => {
+                    let name = module_ref.get().unwrap_or(&module.default_module_name);
+                    if name != module.name {
+                        imports.modules.insert(name.to_string(), None);
+                        raw.insert(name.to_string());
+                    }
+                }
+                ImportRef::Type(type_ref) => {
+                    let name = type_ref.module.get().unwrap_or(&module.default_module_name);
+                    if name != module.name {
+                        imports
+                            .from
+                            .entry(name.to_string())
+                            .or_default()
+                            .insert(type_ref.name);
+                    }
+                }
+                ImportRef::Qualified(TypeRef {
+                    module: module_ref,
+                    name,
+                }) => {
+                    let module_name = module_ref.get().unwrap_or(&module.default_module_name);
+                    if module_name != module.name {
+                        qualified.insert((module_name.to_string(), name));
+                    }
+                }
+            }
+        }
+        let defined = module.defined_names();
+        match module.type_names {
+            TypeNameStyle::Qualified => {
+                for (module_name, _) in qualified {
+                    imports.modules.insert(module_name, None);
+                }
+            }
+            TypeNameStyle::Short => imports.shorten(qualified, &defined),
+        }
+
+        let mut shadowing = defined;
+        shadowing.extend(imports.from.values().flatten().map(String::as_str));
+        shadowing.extend(imports.relative.iter().map(String::as_str));
+        let mut taken: BTreeSet<String> = shadowing.iter().map(|name| name.to_string()).collect();
+        taken.extend(
+            imports
+                .modules
+                .keys()
+                .map(|name| top_level(name).to_string()),
+        );
+        for (name, alias) in &mut imports.modules {
+            if !shadowing.contains(top_level(name)) {
+                continue;
+            }
+            if raw.contains(name) {
+                log::warn!(
+                    "Module `{name}` is shadowed by a name defined in `{}`. The type expressions written as is cannot refer it.",
+                    module.name
+                );
+            }
+            let mut new = format!("_{}", name.replace('.', "_"));
+            while taken.contains(&new) {
+                new.insert(0, '_');
+            }
+            taken.insert(new.clone());
+            *alias = Some(new);
+        }
+        imports
+    }
+
+    /// Import the names by `from module import name` if the short name is unambiguous, or `import module` otherwise
+    fn shorten(&mut self, qualified: BTreeSet<(String, String)>, defined: &BTreeSet<&str>) {
+        let mut modules_of: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (module_name, name) in &qualified {
+            modules_of.entry(name).or_default().insert(module_name);
+        }
+        // `from datetime import datetime` would shadow `import datetime`
+        let referred_modules: BTreeSet<&str> = self
+            .modules
+            .keys()
+            .chain(qualified.iter().map(|(module_name, _)| module_name))
+            .map(|module_name| top_level(module_name))
+            .collect();
+        let local_types: BTreeSet<&str> =
+            self.from.values().flatten().map(String::as_str).collect();
+        let mut short = BTreeSet::new();
+        let mut modules = BTreeSet::new();
+        for (module_name, name) in &qualified {
+            let unambiguous = modules_of[name.as_str()].len() == 1
+                && !defined.contains(name.as_str())
+                && !local_types.contains(name.as_str())
+                && !self.relative.contains(name)
+                && !referred_modules.contains(name.as_str());
+            if unambiguous {
+                short.insert((module_name.clone(), name.clone()));
+            } else {
+                modules.insert(module_name.clone());
+            }
+        }
+        for (module_name, name) in &short {
+            if module_name != "builtins" {
+                self.from
+                    .entry(module_name.clone())
+                    .or_default()
+                    .insert(name.clone());
+            }
+        }
+        for module_name in modules {
+            self.modules.insert(module_name, None);
+        }
+        self.short = short;
+    }
+
+    /// How the names are printed with these imports
+    pub(crate) fn names(&self) -> Names {
+        Names {
+            aliases: self
+                .modules
+                .iter()
+                .filter_map(|(name, alias)| Some((name.clone(), alias.clone()?)))
+                .collect(),
+            short: self.short.clone(),
+            default_module_name: self.default_module_name.clone(),
+        }
+    }
+
+    fn section(&self, module: &str) -> Section {
+        let top = top_level(module);
+        if top == self.first_party {
+            Section::FirstParty
+        } else if STDLIB.contains(&top) {
+            Section::StandardLibrary
+        } else {
+            Section::ThirdParty
+        }
+    }
+}
+
+impl fmt::Display for Imports {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sections: BTreeMap<Section, Vec<String>> = BTreeMap::new();
+        for (name, alias) in &self.modules {
+            let line = match alias {
+                Some(alias) => format!("import {name} as {alias}"),
+                None => format!("import {name}"),
+            };
+            sections.entry(self.section(name)).or_default().push(line);
+        }
+        for (name, types) in &self.from {
+            let line = format!("from {name} import {}", types.iter().join(", "));
+            sections.entry(self.section(name)).or_default().push(line);
+        }
+        if !self.relative.is_empty() {
+            let line = format!("from . import {}", self.relative.iter().join(", "));
+            sections.entry(Section::FirstParty).or_default().push(line);
+        }
+        for (i, lines) in sections.values().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for line in lines {
+                writeln!(f, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn top_level(module: &str) -> &str {
+    module.split('.').next().unwrap_or(module)
+}
+
+/// How the names are printed in the module being printed, see [RenderContext]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Names {
+    /// Aliases of the shadowed modules, e.g. `typing` to `_typing`
+    aliases: BTreeMap<String, String>,
+    /// `(module, name)` printed by the short name
+    short: BTreeSet<(String, String)>,
+    default_module_name: String,
+}
+
+impl Names {
+    /// `module.name` with the alias of the module, or `name` if it is imported by `from module import name`
+    pub(crate) fn qualified_name(&self, module: &ModuleRef, name: &str) -> String {
+        let module = module.get().unwrap_or(&self.default_module_name);
+        if module.is_empty() || self.short.contains(&(module.to_string(), name.to_string())) {
+            return name.to_string();
+        }
+        let module = self.aliases.get(module).map_or(module, String::as_str);
+        format!("{module}.{name}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{stub_type::ModuleRef, TypeExpr, TypeInfo};
+
+    #[test]
+    fn test_resolve() {
+        let mut module = Module {
+            name: "pkg.sub".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        for (name, type_) in [
+            ("typing", TypeInfo::any()),
+            (
+                "path",
+                TypeInfo::with_module("pathlib.Path", "pathlib".into()),
+            ),
+            (
+                "array",
+                TypeInfo::with_module("numpy.ndarray", "numpy".into()),
+            ),
+            ("local", TypeInfo::with_module("pkg.A", ModuleRef::Default)),
+        ] {
+            module.variables.insert(
+                name.to_string(),
+                VariableDef {
+                    name: name.to_string(),
+                    type_,
+                    default: None,
+                },
+            );
+        }
+        module.submodules.insert("leaf".to_string());
+        let imports = Imports::resolve(&module, PythonVersion::MIN);
+        assert_eq!(
+            imports.to_string(),
+            "import pathlib\nimport typing as _typing\n\nimport numpy\n\nimport pkg\nfrom . import leaf\n"
+        );
+        assert_eq!(
+            module.to_string().lines().nth(4),
+            Some("import typing as _typing")
+        );
+        assert!(module.to_string().contains("typing: _typing.Any\n"));
+    }
+
+    #[test]
+    fn test_short_names() {
+        let mut module = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            type_names: TypeNameStyle::Short,
+            ..Default::default()
+        };
+        let sequence = |module: &str| {
+            TypeInfo::generic(
+                TypeExpr::qualified(module, "Sequence"),
+                [TypeInfo::builtin("int")],
+            )
+        };
+        for (name, type_) in [
+            ("a", sequence("typing") | TypeInfo::builtin("str")),
+            ("b", sequence("collections.abc")),
+            ("c", TypeInfo::with_module("pathlib.Path", "pathlib".into())),
+            (
+                "d",
+                TypeInfo::with_module("datetime.datetime", "datetime".into()),
+            ),
+            ("str", TypeInfo::any()),
+        ] {
+            module.variables.insert(
+                name.to_string(),
+                VariableDef {
+                    name: name.to_string(),
+                    type_,
+                    default: None,
+                },
+            );
+        }
+        let imports = Imports::resolve(&module, PythonVersion::MIN);
+        assert_eq!(
+            imports.to_string(),
+            "import builtins\nimport collections.abc\nimport datetime\nimport typing\nfrom pathlib import Path\nfrom typing import Any, Union\n"
+        );
+        let stub = module.to_string();
+        for line in [
+            "a: Union[typing.Sequence[int], builtins.str]",
+            "b: collections.abc.Sequence[int]",
+            "c: Path",
+            "d: datetime.datetime",
+            "str: Any",
+        ] {
+            assert!(stub.contains(line), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_argument_names_do_not_shadow() {
+        let mut module = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        module.function.insert(
+            "f".to_string(),
+            vec![FunctionDef {
+                name: "f".to_string(),
+                args: vec![Arg {
+                    name: "typing".to_string(),
+                    r#type: TypeInfo::any(),
+                    signature: None,
+                }],
+                r#return: TypeInfo::none(),
+                doc: String::new(),
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+            }],
+        );
+        let imports = Imports::resolve(&module, PythonVersion::MIN);
+        assert_eq!(
+            imports.to_string(),
+            "import typing
+"
+        );
+        assert!(module
+            .to_string()
+            .contains("def f(typing:typing.Any) -> None:"));
+    }
+}
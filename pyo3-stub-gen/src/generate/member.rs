@@ -1,4 +1,5 @@
 use crate::{generate::*, type_info::*, TypeInfo};
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
     collections::HashSet,
@@ -6,21 +7,20 @@ use std::{
 };
 
 /// Definition of a class member.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MemberDef {
-    pub name: &'static str,
+    pub name: String,
     pub r#type: TypeInfo,
-    pub doc: &'static str,
+    pub doc: String,
     pub default: Option<String>,
-    pub deprecated: Option<DeprecatedInfo>,
+    pub deprecated: Option<DeprecatedDef>,
 }
 
 impl Import for MemberDef {
-    fn import(&self) -> HashSet<ImportRef> {
-        let mut import = self.r#type.import.clone();
-        // Add typing_extensions import if deprecated
-        if self.deprecated.is_some() {
-            import.insert("typing_extensions".into());
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = self.r#type.import(target);
+        if let Some(deprecated) = &self.deprecated {
+            import.insert(deprecated.import(target));
         }
         import
     }
@@ -29,17 +29,17 @@ impl Import for MemberDef {
 impl From<&MemberInfo> for MemberDef {
     fn from(info: &MemberInfo) -> Self {
         Self {
-            name: info.name,
+            name: info.name.to_string(),
             r#type: (info.r#type)(),
-            doc: info.doc,
+            doc: info.doc.to_string(),
             default: info.default.map(|f| f()),
-            deprecated: info.deprecated.clone(),
+            deprecated: info.deprecated.as_ref().map(DeprecatedDef::from),
         }
     }
 }
 
-impl fmt::Display for MemberDef {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Render for MemberDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
         let indent = indent();
         // Constants cannot have deprecated decorators in Python syntax
         // Log a warning if deprecated is present but will be ignored
@@ -50,12 +50,12 @@ impl fmt::Display for MemberDef {
                 self.name
             );
         }
-        write!(f, "{indent}{}: {}", self.name, self.r#type)?;
+        write!(f, "{indent}{}: {}", self.name, ctx.display(&self.r#type))?;
         if let Some(default) = &self.default {
             write!(f, " = {default}")?;
         }
         writeln!(f)?;
-        docstring::write_docstring(f, self.doc, indent)?;
+        docstring::write_docstring(f, &self.doc, indent)?;
         Ok(())
     }
 }
@@ -63,21 +63,22 @@ impl fmt::Display for MemberDef {
 pub struct GetterDisplay<'a>(pub &'a MemberDef);
 pub struct SetterDisplay<'a>(pub &'a MemberDef);
 
-impl fmt::Display for GetterDisplay<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Render for GetterDisplay<'_> {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
         let indent = indent();
         // Add deprecated decorator if present
         if let Some(deprecated) = &self.0.deprecated {
-            writeln!(f, "{indent}{deprecated}")?;
+            writeln!(f, "{indent}{}", ctx.display(deprecated))?;
         }
         write!(
             f,
             "{indent}@property\n{indent}def {}(self) -> {}:",
-            self.0.name, self.0.r#type
+            self.0.name,
+            ctx.display(&self.0.r#type)
         )?;
         let doc = if let Some(default) = &self.0.default {
             if default == "..." {
-                Cow::Borrowed(self.0.doc)
+                Cow::Borrowed(self.0.doc.as_str())
             } else {
                 Cow::Owned(format!(
                     "{}\n```python\ndefault = {default}\n```",
@@ -85,7 +86,7 @@ impl fmt::Display for GetterDisplay<'_> {
                 ))
             }
         } else {
-            Cow::Borrowed(self.0.doc)
+            Cow::Borrowed(self.0.doc.as_str())
         };
         if !doc.is_empty() {
             writeln!(f)?;
@@ -97,21 +98,23 @@ impl fmt::Display for GetterDisplay<'_> {
     }
 }
 
-impl fmt::Display for SetterDisplay<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Render for SetterDisplay<'_> {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
         let indent = indent();
         // Add deprecated decorator if present
         if let Some(deprecated) = &self.0.deprecated {
-            writeln!(f, "{indent}{deprecated}")?;
+            writeln!(f, "{indent}{}", ctx.display(deprecated))?;
         }
         write!(
             f,
             "{indent}@{}.setter\n{indent}def {}(self, value: {}) -> None:",
-            self.0.name, self.0.name, self.0.r#type
+            self.0.name,
+            self.0.name,
+            ctx.display(&self.0.r#type)
         )?;
         let doc = if let Some(default) = &self.0.default {
             if default == "..." {
-                Cow::Borrowed(self.0.doc)
+                Cow::Borrowed(self.0.doc.as_str())
             } else {
                 Cow::Owned(format!(
                     "{}\n```python\ndefault = {default}\n```",
@@ -119,7 +122,7 @@ impl fmt::Display for SetterDisplay<'_> {
                 ))
             }
         } else {
-            Cow::Borrowed(self.0.doc)
+            Cow::Borrowed(self.0.doc.as_str())
         };
         if !doc.is_empty() {
             writeln!(f)?;
@@ -130,3 +133,21 @@ impl fmt::Display for SetterDisplay<'_> {
         }
     }
 }
+
+impl fmt::Display for MemberDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
+
+impl fmt::Display for GetterDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
+
+impl fmt::Display for SetterDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
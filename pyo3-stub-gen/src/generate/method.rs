@@ -1,32 +1,32 @@
 use crate::stub_type::ImportRef;
 use crate::{generate::*, rule_name::RuleName, type_info::*, TypeInfo};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, fmt};
 
 pub use crate::type_info::MethodType;
 
 /// Definition of a class method.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MethodDef {
-    pub name: &'static str,
+    pub name: String,
     pub args: Vec<Arg>,
     pub r#return: TypeInfo,
-    pub doc: &'static str,
+    pub doc: String,
     pub r#type: MethodType,
     pub is_async: bool,
-    pub deprecated: Option<DeprecatedInfo>,
-    pub type_ignored: Option<IgnoreTarget>,
+    pub deprecated: Option<DeprecatedDef>,
+    pub type_ignored: Option<IgnoreTargetDef>,
 }
 
 impl Import for MethodDef {
-    fn import(&self) -> HashSet<ImportRef> {
-        let mut import = self.r#return.import.clone();
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = self.r#return.import(target);
         for arg in &self.args {
-            import.extend(arg.import().into_iter());
+            import.extend(arg.import(target));
         }
-        // Add typing_extensions import if deprecated
-        if self.deprecated.is_some() {
-            import.insert("typing_extensions".into());
+        if let Some(deprecated) = &self.deprecated {
+            import.insert(deprecated.import(target));
         }
         import
     }
@@ -35,27 +35,27 @@ impl Import for MethodDef {
 impl From<&MethodInfo> for MethodDef {
     fn from(info: &MethodInfo) -> Self {
         Self {
-            name: info.name,
+            name: info.name.to_string(),
             args: info.args.iter().map(Arg::from).collect(),
             r#return: (info.r#return)(),
-            doc: info.doc,
+            doc: info.doc.to_string(),
             r#type: info.r#type,
             is_async: info.is_async,
-            deprecated: info.deprecated.clone(),
-            type_ignored: info.type_ignored,
+            deprecated: info.deprecated.as_ref().map(DeprecatedDef::from),
+            type_ignored: info.type_ignored.as_ref().map(IgnoreTargetDef::from),
         }
     }
 }
 
-impl fmt::Display for MethodDef {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Render for MethodDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
         let indent = indent();
         let mut needs_comma = false;
         let async_ = if self.is_async { "async " } else { "" };
 
         // Add deprecated decorator if present
         if let Some(deprecated) = &self.deprecated {
-            writeln!(f, "{indent}{deprecated}")?;
+            writeln!(f, "{indent}{}", ctx.display(deprecated))?;
         }
 
         match self.r#type {
@@ -80,16 +80,16 @@ impl fmt::Display for MethodDef {
             if needs_comma {
                 write!(f, ", ")?;
             }
-            write!(f, "{arg}")?;
+            write!(f, "{}", ctx.display(arg))?;
             needs_comma = true;
         }
-        write!(f, ") -> {}:", self.r#return)?;
+        write!(f, ") -> {}:", ctx.display(&self.r#return))?;
 
         // Calculate type: ignore comment once
         let type_ignore_comment = if let Some(target) = &self.type_ignored {
             match target {
-                IgnoreTarget::All => Some("  # type: ignore".to_string()),
-                IgnoreTarget::Specified(rules) => {
+                IgnoreTargetDef::All => Some("  # type: ignore".to_string()),
+                IgnoreTargetDef::Specified(rules) => {
                     let rules_str = rules
                         .iter()
                         .map(|r| {
@@ -107,7 +107,7 @@ impl fmt::Display for MethodDef {
             None
         };
 
-        let doc = self.doc;
+        let doc = &self.doc;
         if !doc.is_empty() {
             // Add type: ignore comment for methods with docstrings
             if let Some(comment) = &type_ignore_comment {
@@ -115,7 +115,7 @@ impl fmt::Display for MethodDef {
             }
             writeln!(f)?;
             let double_indent = format!("{indent}{indent}");
-            docstring::write_docstring(f, self.doc, &double_indent)?;
+            docstring::write_docstring(f, &self.doc, &double_indent)?;
         } else {
             write!(f, " ...")?;
             // Add type: ignore comment for methods without docstrings
@@ -127,3 +127,9 @@ impl fmt::Display for MethodDef {
         Ok(())
     }
 }
+
+impl fmt::Display for MethodDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
@@ -1,110 +1,275 @@
-use crate::generate::*;
+use crate::generate::{imports::*, *};
 use crate::stub_type::ImportRef;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::{
-    any::TypeId,
     collections::{BTreeMap, BTreeSet},
     fmt,
 };
 
+/// The first line of every generated stub file.
+/// This tells the generated stub files from the hand-written ones.
+pub(crate) const GENERATED_MARKER: &str = "# This file is automatically generated by pyo3_stub_gen";
+
 /// Type info for a Python (sub-)module. This corresponds to a single `*.pyi` file.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Module {
     pub doc: String,
-    pub class: BTreeMap<TypeId, ClassDef>,
-    pub enum_: BTreeMap<TypeId, EnumDef>,
-    pub function: BTreeMap<&'static str, Vec<FunctionDef>>,
-    pub variables: BTreeMap<&'static str, VariableDef>,
+    pub class: Vec<ClassDef>,
+    pub enum_: Vec<EnumDef>,
+    pub function: BTreeMap<String, Vec<FunctionDef>>,
+    pub variables: BTreeMap<String, VariableDef>,
+    #[serde(default)]
+    pub typed_dict: Vec<TypedDictDef>,
+    #[serde(default)]
+    pub type_alias: Vec<TypeAliasDef>,
+    /// Module-level `TypeVar` and `ParamSpec`, which are not listed in `__all__`
+    #[serde(default)]
+    pub type_var: Vec<TypeVarDef>,
     pub name: String,
     pub default_module_name: String,
+    /// Text written as comments just after the generated marker line
+    pub header: String,
     /// Direct submodules of this module.
     pub submodules: BTreeSet<String>,
+    /// Names excluded from `__all__` in addition to the ones starting with `_`
+    #[serde(default)]
+    pub private: BTreeSet<String>,
+    /// How the type names are printed
+    #[serde(default)]
+    pub type_names: TypeNameStyle,
 }
 
 impl Import for Module {
-    fn import(&self) -> HashSet<ImportRef> {
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
         let mut imports = HashSet::new();
-        for class in self.class.values() {
-            imports.extend(class.import());
+        for class in &self.class {
+            imports.extend(class.import(target));
         }
         for function in self.function.values().flatten() {
-            imports.extend(function.import());
+            imports.extend(function.import(target));
+        }
+        for enum_ in &self.enum_ {
+            imports.extend(enum_.import(target));
+        }
+        for functions in self.function.values() {
+            if functions.len() > 1 {
+                // for @typing.overload
+                imports.insert("typing".into());
+            }
+        }
+        for variable in self.variables.values() {
+            imports.extend(variable.import(target));
+        }
+        for typed_dict in &self.typed_dict {
+            imports.extend(typed_dict.import(target));
+        }
+        for type_alias in &self.type_alias {
+            imports.extend(type_alias.import(target));
+        }
+        for type_var in &self.type_var {
+            imports.extend(type_var.import(target));
         }
         imports
     }
 }
 
-impl fmt::Display for Module {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "# This file is automatically generated by pyo3_stub_gen")?;
-        writeln!(f, "# ruff: noqa: E501, F401")?;
-        if !self.doc.is_empty() {
-            docstring::write_docstring(f, &self.doc, "")?;
+impl Module {
+    /// Merge the definitions of `other`, e.g. parsed from a companion `.pyi` fragment, into this module.
+    ///
+    /// Definitions already existing in this module are not merged, and returned as conflicts,
+    /// e.g. `A.f` for the method `f` of the class `A`.
+    pub fn merge(&mut self, other: Module) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for class in other.class {
+            if let Some(existing) = self.class.iter_mut().find(|c| c.name == class.name) {
+                let prefix = existing.name.clone();
+                conflicts.extend(
+                    existing
+                        .merge(class)
+                        .into_iter()
+                        .map(|name| format!("{prefix}.{name}")),
+                );
+            } else if self.has_name(&class.name) {
+                conflicts.push(class.name);
+            } else {
+                self.class.push(class);
+            }
         }
-        writeln!(f)?;
-        let mut imports = self.import();
-        let any_overloaded = self.function.values().any(|functions| functions.len() > 1);
-        if any_overloaded {
-            imports.insert("typing".into());
+        for (name, functions) in other.function {
+            if self.has_name(&name) {
+                conflicts.push(name);
+            } else {
+                self.function.insert(name, functions);
+            }
+        }
+        for (name, variable) in other.variables {
+            if self.has_name(&name) {
+                conflicts.push(name);
+            } else {
+                self.variables.insert(name, variable);
+            }
         }
+        self.class.sort_by(|a, b| a.name.cmp(&b.name));
+        conflicts
+    }
 
-        // To gather `from submod import A, B, C` style imports
-        let mut type_ref_grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
-        for import_ref in imports.into_iter().sorted() {
-            match import_ref {
-                ImportRef::Module(module_ref) => {
-                    let name = module_ref.get().unwrap_or(&self.default_module_name);
-                    if name != self.name {
-                        writeln!(f, "import {name}")?;
-                    }
-                }
-                ImportRef::Type(type_ref) => {
-                    let module_name = type_ref.module.get().unwrap_or(&self.default_module_name);
-                    if module_name != self.name {
-                        type_ref_grouped
-                            .entry(module_name.to_string())
-                            .or_default()
-                            .push(type_ref.name);
-                    }
-                }
+    /// Public names in this module listed in `__all__`, i.e. classes, enums, typed dicts, type aliases, functions, variables, and submodules
+    /// except the ones starting with `_` or in [Module::private]
+    pub fn public_names(&self) -> BTreeSet<&str> {
+        self.class
+            .iter()
+            .map(|class| class.name.as_str())
+            .chain(self.enum_.iter().map(|enum_| enum_.name.as_str()))
+            .chain(
+                self.typed_dict
+                    .iter()
+                    .map(|typed_dict| typed_dict.name.as_str()),
+            )
+            .chain(
+                self.type_alias
+                    .iter()
+                    .map(|type_alias| type_alias.name.as_str()),
+            )
+            .chain(self.function.keys().map(String::as_str))
+            .chain(self.variables.keys().map(String::as_str))
+            .chain(self.submodules.iter().map(String::as_str))
+            .filter(|name| !name.starts_with('_') && !self.private.contains(*name))
+            .collect()
+    }
+
+    /// Names bound in the module or in a class body, i.e. where the type annotations are evaluated.
+    /// Argument names are not included since they are not in scope of the annotations.
+    pub(crate) fn defined_names(&self) -> BTreeSet<&str> {
+        fn class_names<'a>(class: &'a ClassDef, names: &mut BTreeSet<&'a str>) {
+            names.insert(&class.name);
+            names.extend(class.attrs.iter().map(|attr| attr.name.as_str()));
+            names.extend(class.getter_setters.keys().map(String::as_str));
+            names.extend(class.methods.keys().map(String::as_str));
+            for class in &class.classes {
+                class_names(class, names);
             }
         }
-        for (module_name, type_names) in type_ref_grouped {
-            let mut sorted_type_names = type_names.clone();
-            sorted_type_names.sort();
-            writeln!(
-                f,
-                "from {} import {}",
-                module_name,
-                sorted_type_names.join(", ")
-            )?;
+
+        let mut names = BTreeSet::new();
+        for class in &self.class {
+            class_names(class, &mut names);
         }
-        for submod in &self.submodules {
-            writeln!(f, "from . import {submod}")?;
+        for enum_ in &self.enum_ {
+            names.insert(enum_.name.as_str());
+            names.extend(enum_.variants.iter().map(|(name, _)| name.as_str()));
+            for member in enum_
+                .attrs
+                .iter()
+                .chain(&enum_.getters)
+                .chain(&enum_.setters)
+            {
+                names.insert(&member.name);
+            }
+            names.extend(enum_.methods.iter().map(|method| method.name.as_str()));
+        }
+        for typed_dict in &self.typed_dict {
+            names.insert(typed_dict.name.as_str());
+            names.extend(typed_dict.fields.iter().map(|field| field.name.as_str()));
+        }
+        names.extend(
+            self.type_alias
+                .iter()
+                .map(|type_alias| type_alias.name.as_str()),
+        );
+        names.extend(self.type_var.iter().map(|type_var| type_var.name.as_str()));
+        names.extend(self.function.keys().map(String::as_str));
+        names.extend(self.variables.keys().map(String::as_str));
+        names.extend(self.submodules.iter().map(String::as_str));
+        names
+    }
+
+    fn has_name(&self, name: &str) -> bool {
+        self.class.iter().any(|class| class.name == name)
+            || self.enum_.iter().any(|enum_| enum_.name == name)
+            || self
+                .typed_dict
+                .iter()
+                .any(|typed_dict| typed_dict.name == name)
+            || self
+                .type_alias
+                .iter()
+                .any(|type_alias| type_alias.name == name)
+            || self.type_var.iter().any(|type_var| type_var.name == name)
+            || self.function.contains_key(name)
+            || self.variables.contains_key(name)
+    }
+}
+
+impl Render for Module {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        writeln!(f, "{GENERATED_MARKER}")?;
+        for line in self.header.lines() {
+            if line.is_empty() {
+                writeln!(f, "#")?;
+            } else {
+                writeln!(f, "# {line}")?;
+            }
         }
-        if !self.enum_.is_empty() {
-            writeln!(f, "from enum import Enum")?;
+        writeln!(f, "# ruff: noqa: E501, F401")?;
+        if !self.doc.is_empty() {
+            docstring::write_docstring(f, &self.doc, "")?;
         }
         writeln!(f)?;
+        let imports = Imports::resolve(self, ctx.target);
+        writeln!(f, "{imports}")?;
 
+        let public_names = self.public_names();
+        if !public_names.is_empty() {
+            writeln!(f, "__all__ = [")?;
+            for name in public_names {
+                writeln!(f, "    \"{name}\",")?;
+            }
+            writeln!(f, "]")?;
+            writeln!(f)?;
+        }
+
+        let ctx = RenderContext {
+            target: ctx.target,
+            names: imports.names(),
+        };
+        for type_var in self.type_var.iter().sorted_by_key(|t| &t.name) {
+            write!(f, "{}", ctx.display(type_var))?;
+        }
+        if !self.type_var.is_empty() {
+            writeln!(f)?;
+        }
         for var in self.variables.values() {
-            writeln!(f, "{var}")?;
+            writeln!(f, "{}", ctx.display(var))?;
+        }
+        for type_alias in self.type_alias.iter().sorted_by_key(|t| &t.name) {
+            write!(f, "{}", ctx.display(type_alias))?;
         }
-        for class in self.class.values().sorted_by_key(|class| class.name) {
-            write!(f, "{class}")?;
+        for class in self.class.iter().sorted_by_key(|class| &class.name) {
+            write!(f, "{}", ctx.display(class))?;
         }
-        for enum_ in self.enum_.values().sorted_by_key(|class| class.name) {
-            write!(f, "{enum_}")?;
+        for enum_ in self.enum_.iter().sorted_by_key(|class| &class.name) {
+            write!(f, "{}", ctx.display(enum_))?;
+        }
+        for typed_dict in self.typed_dict.iter().sorted_by_key(|t| &t.name) {
+            write!(f, "{}", ctx.display(typed_dict))?;
         }
         for functions in self.function.values() {
             let overloaded = functions.len() > 1;
             for function in functions {
                 if overloaded {
-                    writeln!(f, "@typing.overload")?;
+                    let overload = ctx.qualified_name(&"typing".into(), "overload");
+                    writeln!(f, "@{overload}")?;
                 }
-                write!(f, "{function}")?;
+                write!(f, "{}", ctx.display(function))?;
             }
         }
         Ok(())
     }
 }
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
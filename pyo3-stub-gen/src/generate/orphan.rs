@@ -0,0 +1,82 @@
+use crate::type_info::PyMethodsInfo;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How to handle `#[gen_stub_pymethods]` whose type is not registered by `#[gen_stub_pyclass]` or others,
+/// e.g. a class behind `cfg` or a class from another crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrphanMethodsPolicy {
+    /// Fail with [OrphanMethodsError]
+    #[default]
+    Error,
+    /// Put the methods on a placeholder class named after the Rust type in the default module
+    Placeholder,
+    /// Skip the methods with a warning
+    Skip,
+}
+
+/// Methods of `#[gen_stub_pymethods]` whose type is not registered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanMethods {
+    /// Rust type name of the `impl` block
+    pub struct_name: &'static str,
+    /// Names of the methods, attributes, getters and setters in the `impl` block
+    pub methods: Vec<&'static str>,
+}
+
+impl From<&PyMethodsInfo> for OrphanMethods {
+    fn from(info: &PyMethodsInfo) -> Self {
+        let methods = info
+            .attrs
+            .iter()
+            .chain(info.getters)
+            .chain(info.setters)
+            .map(|member| member.name)
+            .chain(info.methods.iter().map(|method| method.name))
+            .collect();
+        Self {
+            struct_name: (info.struct_name)(),
+            methods,
+        }
+    }
+}
+
+impl OrphanMethods {
+    /// Python class name for the placeholder class, i.e. the last path segment of the Rust type name without generics
+    pub(crate) fn placeholder_name(&self) -> &'static str {
+        let name = self.struct_name;
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+}
+
+impl fmt::Display for OrphanMethods {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` ({})", self.struct_name, self.methods.join(", "))
+    }
+}
+
+/// Error for `#[gen_stub_pymethods]` whose type is not registered, see [OrphanMethodsPolicy::Error]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanMethodsError {
+    pub orphans: Vec<OrphanMethods>,
+}
+
+impl fmt::Display for OrphanMethodsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "#[gen_stub_pymethods] found for types without #[gen_stub_pyclass]:"
+        )?;
+        for orphan in &self.orphans {
+            writeln!(f, "  {orphan}")?;
+        }
+        write!(
+            f,
+            "Set `orphan-methods = \"placeholder\"` or `\"skip\"` in [tool.pyo3-stub-gen] to generate stubs anyway."
+        )
+    }
+}
+
+impl std::error::Error for OrphanMethodsError {}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::PathBuf};
+
+/// Content of the [PEP 561](https://peps.python.org/pep-0561/) `py.typed` marker file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PyTyped {
+    /// Empty `py.typed`, i.e. the package is fully typed
+    Complete,
+    /// `py.typed` with `partial\n`, i.e. type checkers should fall back to the runtime package for missing stubs
+    Partial,
+}
+
+impl fmt::Display for PyTyped {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PyTyped::Complete => Ok(()),
+            PyTyped::Partial => writeln!(f, "partial"),
+        }
+    }
+}
+
+/// Settings of the [PEP 561](https://peps.python.org/pep-0561/#stub-only-packages) stub-only package output
+///
+/// With this, each top-level package `<pkg>` is written as `<pkg>-stubs` in [StubPackage::dir]
+/// together with a minimal `pyproject.toml` to build the stub-only distribution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StubPackage {
+    /// Directory where the stub-only package is written
+    pub dir: PathBuf,
+    /// Distribution name, e.g. `my_package-stubs`
+    pub name: String,
+    /// Distribution version
+    pub version: String,
+}
+
+impl StubPackage {
+    /// `pyproject.toml` of the stub-only distribution containing the given `*-stubs` directories
+    pub(crate) fn pyproject_toml<'a>(&self, packages: impl IntoIterator<Item = &'a str>) -> String {
+        let packages = packages
+            .into_iter()
+            .map(|package| format!("\"{package}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            r#"{marker}
+[build-system]
+requires = ["hatchling"]
+build-backend = "hatchling.build"
+
+[project]
+name = "{name}"
+version = "{version}"
+
+[tool.hatch.build.targets.wheel]
+packages = [{packages}]
+"#,
+            marker = super::GENERATED_MARKER,
+            name = self.name,
+            version = self.version,
+        )
+    }
+}
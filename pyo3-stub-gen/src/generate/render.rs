@@ -0,0 +1,55 @@
+use crate::{
+    generate::{imports::Names, PythonVersion},
+    stub_type::ModuleRef,
+};
+use std::fmt;
+
+/// How the items are rendered in a stub file, i.e. the targeted Python version and how the names are printed.
+///
+/// [Module] renders its items in the context with the names resolved from its imports,
+/// and the [fmt::Display] of each item renders it in [RenderContext::default],
+/// i.e. for [PythonVersion::MIN] with the fully qualified names.
+///
+/// [Module]: crate::generate::Module
+#[derive(Debug, Clone, Default)]
+pub struct RenderContext {
+    /// Python version targeted by the stub file
+    pub target: PythonVersion,
+    pub(crate) names: Names,
+}
+
+impl RenderContext {
+    pub fn new(target: PythonVersion) -> Self {
+        Self {
+            target,
+            names: Names::default(),
+        }
+    }
+
+    /// [fmt::Display] of `item` rendered in this context
+    pub fn display<'a, T: Render + ?Sized>(&'a self, item: &'a T) -> Rendered<'a, T> {
+        Rendered { item, ctx: self }
+    }
+
+    /// `module.name` with the alias of the module, or `name` if it is imported by `from module import name`
+    pub(crate) fn qualified_name(&self, module: &ModuleRef, name: &str) -> String {
+        self.names.qualified_name(module, name)
+    }
+}
+
+/// An item written in a stub file depending on the [RenderContext]
+pub trait Render {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result;
+}
+
+/// Item rendered in a [RenderContext], see [RenderContext::display]
+pub struct Rendered<'a, T: ?Sized> {
+    item: &'a T,
+    ctx: &'a RenderContext,
+}
+
+impl<T: Render + ?Sized> fmt::Display for Rendered<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.item.render(f, self.ctx)
+    }
+}
@@ -1,81 +1,469 @@
-use crate::{generate::*, pyproject::PyProject, type_info::*};
-use anyhow::{Context, Result};
+use crate::{
+    generate::{custom::*, fragment::*, *},
+    pyproject::{PyProject, Pyo3StubGen},
+    type_info::*,
+};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::{
+    any::TypeId,
     collections::{BTreeMap, BTreeSet},
     fs,
     io::Write,
     path::*,
 };
 
-#[derive(Debug, Clone, PartialEq)]
+/// Version of the JSON snapshot schema written by [StubInfo::to_json].
+///
+/// This is bumped when the schema changes incompatibly.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StubInfo {
     pub modules: BTreeMap<String, Module>,
     pub python_root: PathBuf,
+    /// Write the `py.typed` marker into each top-level package if set
+    pub py_typed: Option<PyTyped>,
+    /// Write a stub-only package instead of the stub files next to the Python sources if set
+    pub stub_package: Option<StubPackage>,
+    /// Python version targeted by the stub files.
+    ///
+    /// This is `tool.pyo3-stub-gen.target-version`, or the lower bound of `project.requires-python` in `pyproject.toml`.
+    #[serde(default)]
+    pub target_version: PythonVersion,
 }
 
 impl StubInfo {
-    /// Initialize [StubInfo] from a `pyproject.toml` file in `CARGO_MANIFEST_DIR`.
-    /// This is automatically set up by the [crate::define_stub_info_gatherer] macro.
+    /// Initialize [StubInfo] from a `pyproject.toml` file.
+    ///
+    /// The `[tool.pyo3-stub-gen]` table of the `pyproject.toml` is also applied, see [Pyo3StubGen].
     pub fn from_pyproject_toml(path: impl AsRef<Path>) -> Result<Self> {
         let pyproject = PyProject::parse_toml(path)?;
-        Ok(StubInfoBuilder::from_pyproject_toml(pyproject).build())
+        StubInfoBuilder::from_pyproject_toml(pyproject).build()
+    }
+
+    /// Initialize [StubInfo] from the project definition of the crate in `CARGO_MANIFEST_DIR`,
+    /// i.e. maturin or setuptools-rust `pyproject.toml`, or `Cargo.toml`. See [PyProject::from_manifest_dir] for details.
+    /// This is automatically set up by the [crate::define_stub_info_gatherer] macro.
+    pub fn from_manifest_dir(manifest_dir: impl AsRef<Path>) -> Result<Self> {
+        let pyproject = PyProject::from_manifest_dir(manifest_dir)?;
+        StubInfoBuilder::from_pyproject_toml(pyproject).build()
     }
 
     /// Initialize [StubInfo] with a specific module name and project root.
     /// This must be placed in your PyO3 library crate, i.e. the same crate where [inventory::submit]ted,
     /// not in the `gen_stub` executables due to [inventory]'s mechanism.
     pub fn from_project_root(default_module_name: String, project_root: PathBuf) -> Result<Self> {
-        Ok(StubInfoBuilder::from_project_root(default_module_name, project_root).build())
+        StubInfoBuilder::from_project_root(default_module_name, project_root).build()
     }
 
-    pub fn generate(&self) -> Result<()> {
-        for (name, module) in self.modules.iter() {
-            // Convert dashes to underscores for Python compatibility
-            let normalized_name = name.replace("-", "_");
-            let path = normalized_name.replace(".", "/");
-            let dest = if module.submodules.is_empty() {
-                self.python_root.join(format!("{path}.pyi"))
-            } else {
-                self.python_root.join(path).join("__init__.pyi")
-            };
+    /// Serialize the whole model into a JSON snapshot with the schema version [SNAPSHOT_VERSION].
+    ///
+    /// The output is stable, i.e. the same [StubInfo] always produces the same JSON,
+    /// so it can be committed and compared between builds.
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct Snapshot<'a> {
+            version: u32,
+            #[serde(flatten)]
+            stub_info: &'a StubInfo,
+        }
+        Ok(serde_json::to_string_pretty(&Snapshot {
+            version: SNAPSHOT_VERSION,
+            stub_info: self,
+        })?)
+    }
+
+    /// Load a JSON snapshot written by [StubInfo::to_json].
+    ///
+    /// The stub files can be rendered from the snapshot without the PyO3 crate, e.g. by [StubInfo::generate].
+    pub fn from_json(json: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+        let Version { version } =
+            serde_json::from_str(json).context("Missing version in stub info snapshot")?;
+        if version != SNAPSHOT_VERSION {
+            bail!("Unsupported stub info snapshot version {version}, expected {SNAPSHOT_VERSION}");
+        }
+        Ok(serde_json::from_str(json)?)
+    }
 
+    /// Directory where the stub files are written,
+    /// i.e. [StubPackage::dir] for stub-only package output and [StubInfo::python_root] otherwise.
+    pub fn output_dir(&self) -> &Path {
+        match &self.stub_package {
+            Some(package) => &package.dir,
+            None => &self.python_root,
+        }
+    }
+
+    /// Check the cross-references between the modules, i.e. every imported class or enum is registered in the referred module,
+    /// and names of classes, functions and variables are unique in each module.
+    ///
+    /// References to the modules not generated here are not checked since they may be defined in other crates.
+    pub fn validate(&self) -> ValidationReport {
+        validate(&self.modules, self.target_version)
+    }
+
+    /// Semantic difference of the Python API from `self`, e.g. the snapshot of the previous release, to `new`.
+    ///
+    /// Each change is classified whether it may break existing Python code, see [ApiDiff].
+    pub fn diff(&self, new: &StubInfo) -> ApiDiff {
+        diff(&self.modules, &new.modules)
+    }
+
+    /// Render all stub files in memory.
+    ///
+    /// The keys are the paths of the files relative to [StubInfo::output_dir],
+    /// i.e. [StubInfo::generate] writes each value to `output_dir().join(key)`.
+    /// This also contains `py.typed` and `pyproject.toml` if they are configured to be generated.
+    ///
+    /// This only depends on the collected items and never reads the existing files.
+    /// Unlike [StubInfo::generate], this does not run [StubInfo::validate],
+    /// so that e.g. a snapshot referring types of other crates can still be rendered,
+    /// and does not keep the custom regions of the existing stub files.
+    pub fn render(&self) -> Result<BTreeMap<PathBuf, String>> {
+        let ctx = RenderContext::new(self.target_version);
+        let mut out = BTreeMap::new();
+        for (name, module) in &self.modules {
+            let path = self.module_path(name, module);
+            out.insert(path, ctx.display(module).to_string());
+        }
+        if self.stub_package.is_some() {
+            for (path, module) in self.package_init_modules(out.keys()) {
+                out.insert(path, ctx.display(&module).to_string());
+            }
+        }
+
+        let packages: BTreeSet<String> = out
+            .keys()
+            .filter(|path| path.components().count() > 1)
+            .filter_map(|path| path.components().next())
+            .map(|top| top.as_os_str().to_string_lossy().to_string())
+            .collect();
+        if let Some(py_typed) = self.py_typed {
+            for path in out.keys() {
+                if path.components().count() == 1 {
+                    log::warn!(
+                        "Cannot place py.typed for a single-file module {path}. It is a top-level module, not a package.",
+                        path = path.display()
+                    );
+                }
+            }
+            for package in &packages {
+                out.insert(Path::new(package).join("py.typed"), py_typed.to_string());
+            }
+        }
+        if let Some(stub_package) = &self.stub_package {
+            out.insert(
+                PathBuf::from("pyproject.toml"),
+                stub_package.pyproject_toml(packages.iter().map(String::as_str)),
+            );
+        }
+        Ok(out)
+    }
+
+    /// [StubInfo::render] after [StubInfo::validate], which fails with [ValidationReport] if it finds problems.
+    ///
+    /// The custom regions of the existing stub files in [StubInfo::output_dir] are inserted into the rendered ones,
+    /// so that hand-written parts survive regeneration.
+    fn render_output(&self) -> Result<BTreeMap<PathBuf, String>> {
+        let report = self.validate();
+        if !report.is_valid() {
+            return Err(report.into());
+        }
+        let mut out = self.render()?;
+        for (path, content) in out.iter_mut() {
+            let existing = self.output_dir().join(path);
+            if path.extension().is_none_or(|ext| ext != "pyi") || !existing.exists() {
+                continue;
+            }
+            let regions = parse_custom_regions(&fs::read_to_string(&existing)?)
+                .with_context(|| format!("Invalid custom region in {}", existing.display()))?;
+            *content = insert_custom_regions(content, &regions);
+        }
+        Ok(out)
+    }
+
+    /// Write the stub files rendered by [StubInfo::render],
+    /// keeping the custom regions of the existing stub files.
+    ///
+    /// This fails with [ValidationReport] without writing anything if [StubInfo::validate] finds problems.
+    pub fn generate(&self) -> Result<()> {
+        for (path, content) in self.render_output()? {
+            let dest = self.output_dir().join(path);
             let dir = dest.parent().context("Cannot get parent directory")?;
             if !dir.exists() {
                 fs::create_dir_all(dir)?;
             }
 
             let mut f = fs::File::create(&dest)?;
-            write!(f, "{module}")?;
-            log::info!(
-                "Generate stub file of a module `{name}` at {dest}",
-                dest = dest.display()
-            );
+            write!(f, "{content}")?;
+            log::info!("Generate {dest}", dest = dest.display());
+        }
+        Ok(())
+    }
+
+    /// Compare the stub files on disk with what [StubInfo::generate] would write without writing anything.
+    ///
+    /// This is useful to detect stale stub files in CI. This also fails if [StubInfo::validate] finds problems.
+    pub fn check(&self) -> Result<CheckReport> {
+        let mut report = CheckReport::default();
+        let mut expected = BTreeSet::new();
+        let rendered = self.render_output()?;
+        for (path, generated) in &rendered {
+            let dest = self.output_dir().join(path);
+            if dest.exists() {
+                let current = fs::read_to_string(&dest)?;
+                if &current != generated {
+                    report.changed.push(ChangedStub {
+                        diff: unified_diff(&dest, &current, generated),
+                        path: dest.clone(),
+                    });
+                }
+            } else {
+                report.missing.push(dest.clone());
+            }
+            expected.insert(dest);
+        }
+        report.extra = self.stale_files(&rendered, &expected)?;
+        Ok(report)
+    }
+
+    /// Remove the stub files which were generated previously but are no longer produced,
+    /// e.g. the stub file of a removed submodule, and return their paths.
+    ///
+    /// Only the files starting with the header line written by [StubInfo::generate] are removed,
+    /// so hand-written stub files are never touched.
+    pub fn remove_stale(&self) -> Result<Vec<PathBuf>> {
+        let rendered = self.render()?;
+        let expected = rendered
+            .keys()
+            .map(|path| self.output_dir().join(path))
+            .collect();
+        let stale = self.stale_files(&rendered, &expected)?;
+        for path in &stale {
+            fs::remove_file(path)?;
+            log::info!("Remove stale stub file {path}", path = path.display());
+        }
+        Ok(stale)
+    }
+
+    /// Merge the companion `.pyi` fragments into the modules, see [Module::merge].
+    ///
+    /// `fragments` maps module names to their fragments, i.e. the resolved [Pyo3StubGen::fragments].
+    /// No fragment is looked up by default, since a file in the Python package would be shipped in the wheel.
+    fn merge_fragments(&mut self, fragments: &BTreeMap<String, PathBuf>) -> Result<()> {
+        let mut conflicts = Vec::new();
+        for (name, path) in fragments {
+            let Some(module) = self.modules.get_mut(name) else {
+                bail!(
+                    "Fragment {} is configured for module `{name}`, which is not generated",
+                    path.display()
+                );
+            };
+            let source = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read fragment {}", path.display()))?;
+            let fragment = parse_fragment(&source, module)
+                .with_context(|| format!("Failed to parse fragment {}", path.display()))?;
+            for conflict in module.merge(fragment) {
+                conflicts.push(FragmentConflict {
+                    fragment: path.clone(),
+                    module: name.clone(),
+                    name: conflict,
+                });
+            }
+            log::info!("Merge fragment {}", path.display());
+        }
+        if !conflicts.is_empty() {
+            return Err(FragmentConflictError { conflicts }.into());
         }
         Ok(())
     }
+
+    /// Path of the stub file for the module relative to [StubInfo::output_dir]
+    fn module_path(&self, name: &str, module: &Module) -> PathBuf {
+        // Convert dashes to underscores for Python compatibility
+        let normalized_name = name.replace("-", "_");
+        let mut parts: Vec<String> = normalized_name.split('.').map(str::to_string).collect();
+        // A top-level module is always a package `<name>-stubs` in a stub-only package
+        let is_package =
+            !module.submodules.is_empty() || (self.stub_package.is_some() && parts.len() == 1);
+        if self.stub_package.is_some() {
+            parts[0].push_str("-stubs");
+        }
+        let path: PathBuf = parts.iter().collect();
+        if is_package {
+            path.join("__init__.pyi")
+        } else {
+            path.with_extension("pyi")
+        }
+    }
+
+    /// Modules for the `__init__.pyi` of the packages in a stub-only package which have no stub file,
+    /// e.g. `mixed-stubs/__init__.pyi` for the mixed layout where only `mixed.main_mod` is a Rust module.
+    ///
+    /// Without them, type checkers cannot resolve the submodules in the stub-only package.
+    fn package_init_modules<'a>(
+        &self,
+        paths: impl IntoIterator<Item = &'a PathBuf>,
+    ) -> BTreeMap<PathBuf, Module> {
+        let paths: BTreeSet<&PathBuf> = paths.into_iter().collect();
+        let mut packages: BTreeMap<PathBuf, BTreeSet<String>> = BTreeMap::new();
+        for path in &paths {
+            let mut child = if path.ends_with("__init__.pyi") {
+                path.parent().unwrap_or(path).to_path_buf()
+            } else {
+                path.with_extension("")
+            };
+            while let Some(parent) = child.parent().filter(|p| !p.as_os_str().is_empty()) {
+                if let Some(name) = child.file_name() {
+                    packages
+                        .entry(parent.to_path_buf())
+                        .or_default()
+                        .insert(name.to_string_lossy().to_string());
+                }
+                child = parent.to_path_buf();
+            }
+        }
+
+        let template = self.modules.values().next().cloned().unwrap_or_default();
+        packages
+            .into_iter()
+            .map(|(dir, submodules)| (dir.join("__init__.pyi"), dir, submodules))
+            .filter(|(init, _, _)| !paths.contains(init))
+            .map(|(init, dir, submodules)| {
+                let name = dir
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .join(".");
+                let module = Module {
+                    name: name.replacen("-stubs", "", 1),
+                    default_module_name: template.default_module_name.clone(),
+                    header: template.header.clone(),
+                    submodules,
+                    type_names: template.type_names,
+                    ..Default::default()
+                };
+                (init, module)
+            })
+            .collect()
+    }
+
+    /// Generated stub files on disk which are not in `expected`
+    fn stale_files(
+        &self,
+        rendered: &BTreeMap<PathBuf, String>,
+        expected: &BTreeSet<PathBuf>,
+    ) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .generated_files_on_disk(rendered)?
+            .into_iter()
+            .filter(|path| !expected.contains(path))
+            .collect())
+    }
+
+    /// Stub files on disk under the top-level packages of the rendered files which were written by this generator.
+    fn generated_files_on_disk(
+        &self,
+        rendered: &BTreeMap<PathBuf, String>,
+    ) -> Result<BTreeSet<PathBuf>> {
+        let top_levels: BTreeSet<String> = rendered
+            .keys()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "pyi"))
+            .filter_map(|path| path.components().next())
+            .map(|top| top.as_os_str().to_string_lossy().to_string())
+            .map(|top| top.strip_suffix(".pyi").map(str::to_string).unwrap_or(top))
+            .collect();
+        let mut candidates = Vec::new();
+        for top in top_levels {
+            candidates.push(self.output_dir().join(format!("{top}.pyi")));
+            collect_stub_files(&self.output_dir().join(top), &mut candidates)?;
+        }
+        let mut out = BTreeSet::new();
+        for path in candidates {
+            if path.is_file() && is_generated(&path)? {
+                out.insert(path);
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn collect_stub_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_stub_files(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "pyi") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_generated(path: &Path) -> Result<bool> {
+    let content = fs::read_to_string(path)?;
+    Ok(content.lines().next() == Some(GENERATED_MARKER))
 }
 
 struct StubInfoBuilder {
     modules: BTreeMap<String, Module>,
+    /// Classes and enums with their module names, indexed by Rust type to merge `#[pymethods]`.
+    /// These are moved into [StubInfoBuilder::modules] at the end.
+    classes: BTreeMap<TypeId, (String, ClassDef)>,
+    enums: BTreeMap<TypeId, (String, EnumDef)>,
     default_module_name: String,
     python_root: PathBuf,
+    config: Pyo3StubGen,
+    /// Resolved [Pyo3StubGen::fragments]
+    fragments: BTreeMap<String, PathBuf>,
+    target_version: PythonVersion,
 }
 
 impl StubInfoBuilder {
     fn from_pyproject_toml(pyproject: PyProject) -> Self {
-        StubInfoBuilder::from_project_root(
-            pyproject.module_name().to_string(),
-            pyproject
-                .python_source()
-                .unwrap_or(PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())),
-        )
+        let python_root = pyproject
+            .output_dir()
+            .or_else(|| pyproject.python_source())
+            .unwrap_or(PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap()));
+        let mut builder =
+            StubInfoBuilder::from_project_root(pyproject.module_name().to_string(), python_root);
+        if let Some(config) = pyproject.stub_gen() {
+            builder.config = config.clone();
+        }
+        builder.fragments = pyproject.fragments();
+        builder.target_version = builder
+            .config
+            .target_version
+            .or_else(|| {
+                pyproject
+                    .project
+                    .requires_python
+                    .as_deref()
+                    .and_then(PythonVersion::from_requires_python)
+            })
+            .unwrap_or_default();
+        builder
     }
 
     fn from_project_root(default_module_name: String, project_root: PathBuf) -> Self {
         Self {
             modules: BTreeMap::new(),
+            classes: BTreeMap::new(),
+            enums: BTreeMap::new(),
             default_module_name,
             python_root: project_root,
+            config: Pyo3StubGen::default(),
+            fragments: BTreeMap::new(),
+            target_version: PythonVersion::default(),
         }
     }
 
@@ -87,6 +475,23 @@ impl StubInfoBuilder {
         module
     }
 
+    /// Drop the modules which do not match `include-modules` or match `exclude-modules`
+    fn filter_modules(&mut self) {
+        // `parent` itself or its submodule
+        fn matches(name: &str, parent: &str) -> bool {
+            name == parent
+                || name
+                    .strip_prefix(parent)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        }
+        let config = &self.config;
+        self.modules.retain(|name, _| {
+            (config.include_modules.is_empty()
+                || config.include_modules.iter().any(|m| matches(name, m)))
+                && !config.exclude_modules.iter().any(|m| matches(name, m))
+        });
+    }
+
     fn register_submodules(&mut self) {
         let mut map: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
         for module in self.modules.keys() {
@@ -106,29 +511,41 @@ impl StubInfoBuilder {
         }
     }
 
+    fn module_name(&mut self, name: Option<&str>) -> String {
+        let name = name.unwrap_or(&self.default_module_name).to_string();
+        self.get_module(Some(&name));
+        name
+    }
+
     fn add_class(&mut self, info: &PyClassInfo) {
-        self.get_module(info.module)
-            .class
-            .insert((info.struct_id)(), ClassDef::from(info));
+        let module = self.module_name(info.module);
+        self.classes
+            .insert((info.struct_id)(), (module, ClassDef::from(info)));
     }
 
     fn add_complex_enum(&mut self, info: &PyComplexEnumInfo) {
+        let module = self.module_name(info.module);
+        self.classes
+            .insert((info.enum_id)(), (module, ClassDef::from(info)));
+    }
+
+    fn add_protocol(&mut self, info: &PyProtocolInfo) {
         self.get_module(info.module)
             .class
-            .insert((info.enum_id)(), ClassDef::from(info));
+            .push(ClassDef::from(info));
     }
 
     fn add_enum(&mut self, info: &PyEnumInfo) {
-        self.get_module(info.module)
-            .enum_
-            .insert((info.enum_id)(), EnumDef::from(info));
+        let module = self.module_name(info.module);
+        self.enums
+            .insert((info.enum_id)(), (module, EnumDef::from(info)));
     }
 
     fn add_function(&mut self, info: &PyFunctionInfo) {
         let target = self
             .get_module(info.module)
             .function
-            .entry(info.name)
+            .entry(info.name.to_string())
             .or_default();
         target.push(FunctionDef::from(info));
     }
@@ -136,95 +553,117 @@ impl StubInfoBuilder {
     fn add_variable(&mut self, info: &PyVariableInfo) {
         self.get_module(Some(info.module))
             .variables
-            .insert(info.name, VariableDef::from(info));
+            .insert(info.name.to_string(), VariableDef::from(info));
+    }
+
+    fn add_typed_dict(&mut self, info: &PyTypedDictInfo) {
+        self.get_module(info.module)
+            .typed_dict
+            .push(TypedDictDef::from(info));
+    }
+
+    fn add_type_alias(&mut self, info: &PyTypeAliasInfo) {
+        self.get_module(info.module)
+            .type_alias
+            .push(TypeAliasDef::from(info));
+    }
+
+    fn add_type_var(&mut self, info: &PyTypeVarInfo) {
+        self.get_module(Some(info.module))
+            .type_var
+            .push(TypeVarDef::from(info));
     }
 
     fn add_module_doc(&mut self, info: &ModuleDocInfo) {
         self.get_module(Some(info.module)).doc = (info.doc)();
     }
 
-    fn add_methods(&mut self, info: &PyMethodsInfo) {
+    fn add_private(&mut self, info: &PrivateInfo) {
+        self.get_module(info.module)
+            .private
+            .insert(info.name.to_string());
+    }
+
+    /// Add methods to the registered class or enum, and handle them by [OrphanMethodsPolicy] if it is not registered.
+    fn add_methods(&mut self, info: &PyMethodsInfo) -> std::result::Result<(), OrphanMethods> {
         let struct_id = (info.struct_id)();
-        for module in self.modules.values_mut() {
-            if let Some(entry) = module.class.get_mut(&struct_id) {
-                for attr in info.attrs {
-                    entry.attrs.push(MemberDef {
-                        name: attr.name,
-                        r#type: (attr.r#type)(),
-                        doc: attr.doc,
-                        default: attr.default.map(|f| f()),
-                        deprecated: attr.deprecated.clone(),
-                    });
-                }
-                for getter in info.getters {
-                    entry
-                        .getter_setters
-                        .entry(getter.name.to_string())
-                        .or_default()
-                        .0 = Some(MemberDef {
-                        name: getter.name,
-                        r#type: (getter.r#type)(),
-                        doc: getter.doc,
-                        default: getter.default.map(|f| f()),
-                        deprecated: getter.deprecated.clone(),
-                    });
-                }
-                for setter in info.setters {
-                    entry
-                        .getter_setters
-                        .entry(setter.name.to_string())
-                        .or_default()
-                        .1 = Some(MemberDef {
-                        name: setter.name,
-                        r#type: (setter.r#type)(),
-                        doc: setter.doc,
-                        default: setter.default.map(|f| f()),
-                        deprecated: setter.deprecated.clone(),
-                    });
-                }
-                for method in info.methods {
-                    let entries = entry.methods.entry(method.name.to_string()).or_default();
-                    entries.push(MethodDef::from(method));
-                }
-                return;
-            } else if let Some(entry) = module.enum_.get_mut(&struct_id) {
-                for attr in info.attrs {
-                    entry.attrs.push(MemberDef {
-                        name: attr.name,
-                        r#type: (attr.r#type)(),
-                        doc: attr.doc,
-                        default: attr.default.map(|f| f()),
-                        deprecated: attr.deprecated.clone(),
-                    });
-                }
-                for getter in info.getters {
-                    entry.getters.push(MemberDef {
-                        name: getter.name,
-                        r#type: (getter.r#type)(),
-                        doc: getter.doc,
-                        default: getter.default.map(|f| f()),
-                        deprecated: getter.deprecated.clone(),
-                    });
-                }
-                for setter in info.setters {
-                    entry.setters.push(MemberDef {
-                        name: setter.name,
-                        r#type: (setter.r#type)(),
-                        doc: setter.doc,
-                        default: setter.default.map(|f| f()),
-                        deprecated: setter.deprecated.clone(),
-                    });
-                }
-                for method in info.methods {
-                    entry.methods.push(MethodDef::from(method))
-                }
-                return;
+        if let Some((_, entry)) = self.classes.get_mut(&struct_id) {
+            for attr in info.attrs {
+                entry.attrs.push(MemberDef::from(attr));
+            }
+            for getter in info.getters {
+                entry
+                    .getter_setters
+                    .entry(getter.name.to_string())
+                    .or_default()
+                    .0 = Some(MemberDef::from(getter));
+            }
+            for setter in info.setters {
+                entry
+                    .getter_setters
+                    .entry(setter.name.to_string())
+                    .or_default()
+                    .1 = Some(MemberDef::from(setter));
+            }
+            for method in info.methods {
+                let entries = entry.methods.entry(method.name.to_string()).or_default();
+                entries.push(MethodDef::from(method));
+            }
+            return Ok(());
+        } else if let Some((_, entry)) = self.enums.get_mut(&struct_id) {
+            for attr in info.attrs {
+                entry.attrs.push(MemberDef::from(attr));
+            }
+            for getter in info.getters {
+                entry.getters.push(MemberDef::from(getter));
+            }
+            for setter in info.setters {
+                entry.setters.push(MemberDef::from(setter));
+            }
+            for method in info.methods {
+                entry.methods.push(MethodDef::from(method))
+            }
+            return Ok(());
+        }
+
+        let orphan = OrphanMethods::from(info);
+        match self.config.orphan_methods.unwrap_or_default() {
+            OrphanMethodsPolicy::Error => Err(orphan),
+            OrphanMethodsPolicy::Placeholder => {
+                log::warn!(
+                    "Put #[gen_stub_pymethods] for {orphan} on a placeholder class `{name}` since the type is not registered.",
+                    name = orphan.placeholder_name()
+                );
+                let module = self.module_name(None);
+                self.classes.insert(
+                    struct_id,
+                    (
+                        module,
+                        ClassDef {
+                            name: orphan.placeholder_name().to_string(),
+                            doc: String::new(),
+                            attrs: Vec::new(),
+                            getter_setters: IndexMap::new(),
+                            methods: IndexMap::new(),
+                            bases: Vec::new(),
+                            type_params: Vec::new(),
+                            classes: Vec::new(),
+                            match_args: None,
+                        },
+                    ),
+                );
+                self.add_methods(info)
+            }
+            OrphanMethodsPolicy::Skip => {
+                log::warn!(
+                    "Skip #[gen_stub_pymethods] for {orphan} since the type is not registered."
+                );
+                Ok(())
             }
         }
-        unreachable!("Missing struct_id/enum_id = {:?}", struct_id);
     }
 
-    fn build(mut self) -> StubInfo {
+    fn build(mut self) -> Result<StubInfo> {
         for info in inventory::iter::<PyClassInfo> {
             self.add_class(info);
         }
@@ -234,22 +673,628 @@ impl StubInfoBuilder {
         for info in inventory::iter::<PyEnumInfo> {
             self.add_enum(info);
         }
-        for info in inventory::iter::<PyFunctionInfo> {
+        for info in inventory::iter::<PyProtocolInfo> {
+            self.add_protocol(info);
+        }
+        // Overloads are ordered as in the source
+        for info in inventory::iter::<PyFunctionInfo>
+            .into_iter()
+            .sorted_by_key(|info| info.location)
+        {
             self.add_function(info);
         }
         for info in inventory::iter::<PyVariableInfo> {
             self.add_variable(info);
         }
+        for info in inventory::iter::<PyTypedDictInfo> {
+            self.add_typed_dict(info);
+        }
+        for info in inventory::iter::<PyTypeAliasInfo> {
+            self.add_type_alias(info);
+        }
+        for info in inventory::iter::<PyTypeVarInfo> {
+            self.add_type_var(info);
+        }
         for info in inventory::iter::<ModuleDocInfo> {
             self.add_module_doc(info);
         }
-        for info in inventory::iter::<PyMethodsInfo> {
-            self.add_methods(info);
+        for info in inventory::iter::<PrivateInfo> {
+            self.add_private(info);
+        }
+        let mut orphans = Vec::new();
+        for info in inventory::iter::<PyMethodsInfo>
+            .into_iter()
+            .sorted_by_key(|info| info.location)
+        {
+            if let Err(orphan) = self.add_methods(info) {
+                orphans.push(orphan);
+            }
+        }
+        if !orphans.is_empty() {
+            return Err(OrphanMethodsError { orphans }.into());
+        }
+        let fragments = std::mem::take(&mut self.fragments);
+        let mut stub = self.finish();
+        stub.merge_fragments(&fragments)?;
+        Ok(stub)
+    }
+
+    fn finish(mut self) -> StubInfo {
+        for (module, class) in std::mem::take(&mut self.classes).into_values() {
+            self.get_module(Some(&module)).class.push(class);
+        }
+        for (module, enum_) in std::mem::take(&mut self.enums).into_values() {
+            self.get_module(Some(&module)).enum_.push(enum_);
         }
+        let declared: Vec<(String, TypeVarDef)> = self
+            .modules
+            .iter()
+            .flat_map(|(name, module)| {
+                module
+                    .type_var
+                    .iter()
+                    .map(move |type_var| (name.clone(), type_var.clone()))
+            })
+            .collect();
+        for (name, module) in self.modules.iter_mut() {
+            module.class.sort_by(|a, b| a.name.cmp(&b.name));
+            module.enum_.sort_by(|a, b| a.name.cmp(&b.name));
+            module.typed_dict.sort_by(|a, b| a.name.cmp(&b.name));
+            module.type_alias.sort_by(|a, b| a.name.cmp(&b.name));
+            module.type_var.sort_by(|a, b| a.name.cmp(&b.name));
+            for class in &mut module.class {
+                for type_param in &mut class.type_params {
+                    // The declaration in the module of the class is preferred, and one in another module is imported
+                    if let Some((declaring, type_var)) = declared
+                        .iter()
+                        .filter(|(_, type_var)| type_var.name == type_param.name)
+                        .min_by_key(|(declaring, _)| declaring != name)
+                    {
+                        *type_param = TypeVarDef {
+                            module: (declaring != name).then(|| declaring.clone()),
+                            ..type_var.clone()
+                        };
+                    }
+                }
+            }
+        }
+        self.filter_modules();
         self.register_submodules();
+        if let Some(header) = &self.config.header {
+            for module in self.modules.values_mut() {
+                module.header = header.clone();
+            }
+        }
+        if let Some(type_names) = self.config.type_names {
+            for module in self.modules.values_mut() {
+                module.type_names = type_names;
+            }
+        }
         StubInfo {
             modules: self.modules,
             python_root: self.python_root,
+            py_typed: self.config.py_typed,
+            stub_package: None,
+            target_version: self.target_version,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TypeInfo;
+
+    fn stub_info(python_root: PathBuf, names: &[&str]) -> StubInfo {
+        let modules = names
+            .iter()
+            .map(|name| {
+                let module = Module {
+                    name: name.to_string(),
+                    default_module_name: names[0].to_string(),
+                    ..Default::default()
+                };
+                (name.to_string(), module)
+            })
+            .collect();
+        StubInfo {
+            modules,
+            python_root,
+            py_typed: None,
+            stub_package: None,
+            target_version: PythonVersion::default(),
+        }
+    }
+
+    #[test]
+    fn test_builder_config() {
+        let mut builder =
+            StubInfoBuilder::from_project_root("pkg".to_string(), PathBuf::from("unused"));
+        for name in ["pkg", "pkg.a", "pkg.a.x", "pkg.ab", "other"] {
+            builder.get_module(Some(name));
+        }
+        builder.config = Pyo3StubGen {
+            header: Some("Copyright\n\nExample".to_string()),
+            include_modules: vec!["pkg".to_string()],
+            exclude_modules: vec!["pkg.a".to_string()],
+            py_typed: Some(PyTyped::Complete),
+            ..Default::default()
+        };
+        let stub = builder.finish();
+        assert_eq!(
+            stub.modules.keys().collect::<Vec<_>>(),
+            vec!["pkg", "pkg.ab"]
+        );
+        assert_eq!(
+            stub.modules["pkg"].submodules,
+            BTreeSet::from(["ab".to_string()])
+        );
+        assert_eq!(stub.py_typed, Some(PyTyped::Complete));
+        assert!(stub.modules["pkg"]
+            .to_string()
+            .starts_with(&format!("{GENERATED_MARKER}\n# Copyright\n#\n# Example\n")));
+    }
+
+    #[test]
+    fn test_type_param_from_another_module() {
+        let mut builder =
+            StubInfoBuilder::from_project_root("pkg".to_string(), PathBuf::from("unused"));
+        builder.get_module(Some("pkg")).type_var.push(TypeVarDef {
+            name: "T".to_string(),
+            kind: TypeVarKind::TypeVar,
+            bound: Some(TypeInfo::builtin("float")),
+            constraints: Vec::new(),
+            module: None,
+        });
+        builder.get_module(Some("pkg.a")).class.push(ClassDef {
+            name: "Store".to_string(),
+            doc: String::new(),
+            attrs: Vec::new(),
+            getter_setters: IndexMap::new(),
+            methods: IndexMap::new(),
+            bases: Vec::new(),
+            type_params: vec![TypeVarDef::undeclared("T")],
+            classes: Vec::new(),
+            match_args: None,
+        });
+        let mut stub = builder.finish();
+        assert!(stub.validate().is_valid(), "{}", stub.validate());
+
+        let rendered = stub.render().unwrap()[Path::new("pkg/a.pyi")].clone();
+        assert!(rendered.contains("from pkg import T\n"), "{rendered}");
+        assert!(rendered.contains("class Store(typing.Generic[T]):\n"));
+
+        stub.target_version = PythonVersion::new(3, 12);
+        let rendered = stub.render().unwrap()[Path::new("pkg/a.pyi")].clone();
+        assert!(!rendered.contains("from pkg import T"), "{rendered}");
+        assert!(rendered.contains("class Store[T: builtins.float]:\n"));
+    }
+
+    struct Orphan;
+
+    static ORPHAN_METHODS: PyMethodsInfo = PyMethodsInfo {
+        struct_id: std::any::TypeId::of::<Orphan>,
+        struct_name: std::any::type_name::<Orphan>,
+        attrs: &[],
+        getters: &[],
+        setters: &[],
+        methods: &[MethodInfo {
+            name: "orphan_method",
+            args: &[],
+            r#return: crate::TypeInfo::none,
+            doc: "",
+            r#type: MethodType::Instance,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+        }],
+        location: std::panic::Location::caller(),
+    };
+
+    #[test]
+    fn test_orphan_methods() {
+        let mut builder =
+            StubInfoBuilder::from_project_root("pkg".to_string(), PathBuf::from("unused"));
+        let orphan = builder.add_methods(&ORPHAN_METHODS).unwrap_err();
+        assert_eq!(
+            orphan,
+            OrphanMethods {
+                struct_name: "pyo3_stub_gen::generate::stub_info::test::Orphan",
+                methods: vec!["orphan_method"],
+            }
+        );
+        assert_eq!(orphan.placeholder_name(), "Orphan");
+
+        builder.config.orphan_methods = Some(OrphanMethodsPolicy::Skip);
+        builder.add_methods(&ORPHAN_METHODS).unwrap();
+        assert!(builder.classes.is_empty());
+
+        builder.config.orphan_methods = Some(OrphanMethodsPolicy::Placeholder);
+        builder.add_methods(&ORPHAN_METHODS).unwrap();
+        let (module, class) = &builder.classes[&std::any::TypeId::of::<Orphan>()];
+        assert_eq!(module, "pkg");
+        assert_eq!(class.name, "Orphan");
+        assert!(class.methods.contains_key("orphan_method"));
+    }
+
+    #[test]
+    fn test_json_snapshot() -> Result<()> {
+        let mut stub = stub_info(PathBuf::from("unused"), &["pkg"]);
+        stub.py_typed = Some(PyTyped::Partial);
+        let module = stub.modules.get_mut("pkg").unwrap();
+        module.function.insert(
+            "f".to_string(),
+            vec![FunctionDef {
+                name: "f".to_string(),
+                args: vec![Arg {
+                    name: "x".to_string(),
+                    r#type: crate::TypeInfo::builtin("int")
+                        | crate::TypeInfo::locally_defined("A", Default::default()),
+                    signature: Some(SignatureArgDef::Assign {
+                        default: "1".to_string(),
+                    }),
+                }],
+                r#return: crate::TypeInfo::none(),
+                doc: "doc".to_string(),
+                is_async: false,
+                deprecated: Some(DeprecatedDef {
+                    since: Some("1.0".to_string()),
+                    note: None,
+                }),
+                type_ignored: Some(IgnoreTargetDef::Specified(vec!["arg-type".to_string()])),
+            }],
+        );
+        module.class.push(ClassDef {
+            name: "A".to_string(),
+            doc: String::new(),
+            attrs: Vec::new(),
+            getter_setters: IndexMap::new(),
+            methods: IndexMap::new(),
+            bases: Vec::new(),
+            type_params: Vec::new(),
+            classes: Vec::new(),
+            match_args: None,
+        });
+
+        let json = stub.to_json()?;
+        assert!(json.contains(&format!("\"version\": {SNAPSHOT_VERSION}")));
+        // Stable output
+        assert_eq!(json, stub.clone().to_json()?);
+
+        let loaded = StubInfo::from_json(&json)?;
+        assert_eq!(loaded, stub);
+        assert_eq!(loaded.render()?, stub.render()?);
+
+        let unsupported = json.replacen(
+            &format!("\"version\": {SNAPSHOT_VERSION}"),
+            "\"version\": 0",
+            1,
+        );
+        assert!(StubInfo::from_json(&unsupported).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let mut stub = stub_info(
+            PathBuf::from("unused"),
+            &["my-pkg", "my-pkg.sub", "my-pkg.sub.leaf"],
+        );
+        for (name, sub) in [("my-pkg", "sub"), ("my-pkg.sub", "leaf")] {
+            stub.modules
+                .get_mut(name)
+                .unwrap()
+                .submodules
+                .insert(sub.to_string());
+        }
+        let rendered = stub.render()?;
+        assert_eq!(
+            rendered.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("my_pkg/__init__.pyi"),
+                Path::new("my_pkg/sub/__init__.pyi"),
+                Path::new("my_pkg/sub/leaf.pyi"),
+            ]
+        );
+        assert_eq!(
+            rendered[Path::new("my_pkg/sub/leaf.pyi")],
+            stub.modules["my-pkg.sub.leaf"].to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_dunder_all() {
+        let mut stub = stub_info(PathBuf::from("unused"), &["pkg"]);
+        let module = stub.modules.get_mut("pkg").unwrap();
+        assert!(!module.to_string().contains("__all__"));
+
+        for name in ["b", "a", "_hidden", "internal"] {
+            module.variables.insert(
+                name.to_string(),
+                VariableDef {
+                    name: name.to_string(),
+                    type_: crate::TypeInfo::builtin("int"),
+                    default: None,
+                },
+            );
+        }
+        module.submodules.insert("sub".to_string());
+        module.private.insert("internal".to_string());
+        assert_eq!(
+            module.public_names().into_iter().collect::<Vec<_>>(),
+            vec!["a", "b", "sub"]
+        );
+        assert!(module
+            .to_string()
+            .contains("__all__ = [\n    \"a\",\n    \"b\",\n    \"sub\",\n]\n"));
+    }
+
+    #[test]
+    fn test_render_py_typed() -> Result<()> {
+        let mut stub = stub_info(PathBuf::from("unused"), &["pkg.main_mod", "single"]);
+        stub.py_typed = Some(PyTyped::Partial);
+        let rendered = stub.render()?;
+        assert_eq!(
+            rendered.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("pkg/main_mod.pyi"),
+                Path::new("pkg/py.typed"),
+                Path::new("single.pyi"),
+            ]
+        );
+        assert_eq!(rendered[Path::new("pkg/py.typed")], "partial\n");
+
+        stub.py_typed = Some(PyTyped::Complete);
+        assert_eq!(stub.render()?[Path::new("pkg/py.typed")], "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_stub_package() -> Result<()> {
+        let mut stub = stub_info(PathBuf::from("unused"), &["my-pkg", "my-pkg.sub"]);
+        stub.modules
+            .get_mut("my-pkg")
+            .unwrap()
+            .submodules
+            .insert("sub".to_string());
+        let mut single = stub_info(PathBuf::from("unused"), &["single"]);
+        for stub in [&mut stub, &mut single] {
+            stub.stub_package = Some(StubPackage {
+                dir: PathBuf::from("stubs"),
+                name: "my-pkg-stubs".to_string(),
+                version: "0.1.0".to_string(),
+            });
+        }
+        assert_eq!(stub.output_dir(), Path::new("stubs"));
+
+        let rendered = stub.render()?;
+        assert_eq!(
+            rendered.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("my_pkg-stubs/__init__.pyi"),
+                Path::new("my_pkg-stubs/sub.pyi"),
+                Path::new("pyproject.toml"),
+            ]
+        );
+        let pyproject = &rendered[Path::new("pyproject.toml")];
+        assert!(pyproject.contains(r#"name = "my-pkg-stubs""#));
+        assert!(pyproject.contains(r#"packages = ["my_pkg-stubs"]"#));
+
+        assert_eq!(
+            single.render()?.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("pyproject.toml"),
+                Path::new("single-stubs/__init__.pyi"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_stub_package_mixed_layout() -> Result<()> {
+        // Only `mixed.main_mod` and `mixed.sub.leaf` are Rust modules, `mixed` and `mixed.sub` are Python packages
+        let mut stub = stub_info(
+            PathBuf::from("unused"),
+            &["mixed.main_mod", "mixed.sub.leaf"],
+        );
+        stub.stub_package = Some(StubPackage {
+            dir: PathBuf::from("stubs"),
+            name: "mixed-stubs".to_string(),
+            version: "0.1.0".to_string(),
+        });
+
+        let rendered = stub.render()?;
+        assert_eq!(
+            rendered.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("mixed-stubs/__init__.pyi"),
+                Path::new("mixed-stubs/main_mod.pyi"),
+                Path::new("mixed-stubs/sub/__init__.pyi"),
+                Path::new("mixed-stubs/sub/leaf.pyi"),
+                Path::new("pyproject.toml"),
+            ]
+        );
+        let init = &rendered[Path::new("mixed-stubs/__init__.pyi")];
+        assert!(init.starts_with(GENERATED_MARKER));
+        assert!(init.contains("from . import main_mod, sub\n"));
+        assert!(
+            rendered[Path::new("mixed-stubs/sub/__init__.pyi")].contains("from . import leaf\n")
+        );
+        assert!(rendered[Path::new("pyproject.toml")].contains(r#"packages = ["mixed-stubs"]"#));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_without_validation() -> Result<()> {
+        let mut stub = stub_info(PathBuf::from("unused"), &["pkg", "pkg.sub"]);
+        // `pkg.sub.Missing` is not registered
+        stub.modules.get_mut("pkg").unwrap().variables.insert(
+            "x".to_string(),
+            VariableDef {
+                name: "x".to_string(),
+                type_: crate::TypeInfo::locally_defined("Missing", "pkg.sub".into()),
+                default: None,
+            },
+        );
+        assert!(!stub.validate().is_valid());
+        assert!(stub.render()?[Path::new("pkg.pyi")].contains("x: Missing"));
+        assert!(stub.check().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("pyo3-stub-gen-check-{}", std::process::id()));
+        if root.exists() {
+            fs::remove_dir_all(&root)?;
+        }
+
+        let mut stub = stub_info(root.clone(), &["pkg", "pkg.sub"]);
+        stub.modules
+            .get_mut("pkg")
+            .unwrap()
+            .submodules
+            .insert("sub".to_string());
+        let init = root.join("pkg/__init__.pyi");
+        let sub = root.join("pkg/sub.pyi");
+
+        let report = stub.check()?;
+        assert_eq!(report.missing, vec![init.clone(), sub.clone()]);
+        assert!(report.extra.is_empty() && report.changed.is_empty());
+
+        stub.generate()?;
+        assert!(stub.check()?.is_up_to_date());
+
+        // Custom regions are kept by regeneration
+        let custom = "# pyo3-stub-gen: begin custom\ndef __getattr__(name: str) -> int: ...\n# pyo3-stub-gen: end custom\n";
+        fs::write(&init, format!("{}{custom}", fs::read_to_string(&init)?))?;
+        assert!(stub.check()?.is_up_to_date());
+        stub.generate()?;
+        assert!(fs::read_to_string(&init)?.ends_with(custom));
+        // while render does not read the existing files
+        assert!(!stub.render()?[Path::new("pkg/__init__.pyi")].contains(custom));
+
+        // Hand-written stubs are never reported as extra
+        fs::write(root.join("pkg/manual.pyi"), "def f() -> None: ...\n")?;
+        // while stale generated ones are
+        fs::copy(&sub, root.join("pkg/old.pyi"))?;
+        fs::write(&sub, format!("{GENERATED_MARKER}\nx: int\n"))?;
+
+        let report = stub.check()?;
+        assert!(report.missing.is_empty());
+        assert_eq!(report.extra, vec![root.join("pkg/old.pyi")]);
+        assert_eq!(report.changed.len(), 1);
+        assert_eq!(report.changed[0].path, sub);
+        assert!(report.changed[0].diff.contains("-x: int"));
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_fragments() -> Result<()> {
+        let root =
+            std::env::temp_dir().join(format!("pyo3-stub-gen-fragments-{}", std::process::id()));
+        if root.exists() {
+            fs::remove_dir_all(&root)?;
+        }
+        fs::create_dir_all(&root)?;
+
+        let mut stub = stub_info(root.clone(), &["pkg"]);
+        let mut class = ClassDef {
+            name: "A".to_string(),
+            doc: String::new(),
+            attrs: Vec::new(),
+            getter_setters: IndexMap::new(),
+            methods: IndexMap::new(),
+            bases: Vec::new(),
+            type_params: Vec::new(),
+            classes: Vec::new(),
+            match_args: None,
+        };
+        class.methods.insert("f".to_string(), Vec::new());
+        stub.modules.get_mut("pkg").unwrap().class.push(class);
+
+        // `<module>.extra.pyi` is not merged unless configured, since it would be shipped in the wheel
+        let extra = root.join("pkg.extra.pyi");
+        fs::write(
+            &extra,
+            "import typing\n\nclass A:\n    def g(self) -> typing.Any: ...\n\ndef h() -> None: ...\n",
+        )?;
+        let mut merged = stub.clone();
+        merged.merge_fragments(&BTreeMap::new())?;
+        assert_eq!(merged, stub);
+
+        let configured = BTreeMap::from([("pkg".to_string(), extra)]);
+        let mut merged = stub.clone();
+        merged.merge_fragments(&configured)?;
+        let rendered = merged.render()?[Path::new("pkg.pyi")].clone();
+        assert!(rendered.contains("import typing\n"));
+        assert!(rendered.contains("    def g(self) -> typing.Any: ...\n"));
+        assert!(rendered.contains("def h() -> None: ...\n"));
+
+        // The same fragment is used for a stub-only package
+        let mut merged = stub.clone();
+        merged.stub_package = Some(StubPackage {
+            dir: root.join("stubs"),
+            name: "pkg-stubs".to_string(),
+            version: "0.1.0".to_string(),
+        });
+        merged.merge_fragments(&configured)?;
+        assert!(merged.render()?[Path::new("pkg-stubs/__init__.pyi")]
+            .contains("def h() -> None: ...\n"));
+
+        let fragment = root.join("fragment.pyi");
+        fs::write(&fragment, "class A:\n    def f(self) -> None: ...\n")?;
+        let err = stub
+            .clone()
+            .merge_fragments(&BTreeMap::from([("pkg".to_string(), fragment.clone())]))
+            .unwrap_err()
+            .downcast::<FragmentConflictError>()?;
+        assert_eq!(
+            err.conflicts,
+            vec![FragmentConflict {
+                fragment,
+                module: "pkg".to_string(),
+                name: "A.f".to_string(),
+            }]
+        );
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_stale() -> Result<()> {
+        let root =
+            std::env::temp_dir().join(format!("pyo3-stub-gen-remove-stale-{}", std::process::id()));
+        if root.exists() {
+            fs::remove_dir_all(&root)?;
+        }
+
+        // `pkg` had a submodule `pkg.sub` previously
+        let mut old = stub_info(root.clone(), &["pkg", "pkg.sub"]);
+        old.modules
+            .get_mut("pkg")
+            .unwrap()
+            .submodules
+            .insert("sub".to_string());
+        old.generate()?;
+        fs::write(root.join("pkg/manual.pyi"), "def f() -> None: ...\n")?;
+
+        let new = stub_info(root.clone(), &["pkg"]);
+        new.generate()?;
+        let removed = new.remove_stale()?;
+        assert_eq!(
+            removed,
+            vec![root.join("pkg/__init__.pyi"), root.join("pkg/sub.pyi")]
+        );
+        assert!(root.join("pkg.pyi").exists());
+        assert!(root.join("pkg/manual.pyi").exists());
+        assert!(new.check()?.is_up_to_date());
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
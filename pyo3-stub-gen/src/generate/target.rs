@@ -0,0 +1,161 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Python version targeted by the stub files, e.g. `3.10`.
+///
+/// This decides the syntax used in the stub files:
+///
+/// - `X | None` and `X | Y` since 3.10, `typing.Optional[X]` and `typing.Union[X, Y]` before
+/// - `typing.ParamSpec` and `typing.TypeAlias` since 3.10, `typing_extensions` before
+/// - `typing.Self`, `typing.Never`, `typing.Required` and `typing.NotRequired` since 3.11, `override` since 3.12, and `warnings.deprecated` since 3.13.
+///   `typing_extensions` is used before.
+/// - `types.CapsuleType` since 3.13, `typing_extensions.CapsuleType` before
+/// - `types.EllipsisType` since 3.10, `builtins.ellipsis` before
+/// - `type X = ...` and `class C[T]` (PEP 695) since 3.12, `X: TypeAlias = ...` and `class C(typing.Generic[T])` before
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PythonVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl PythonVersion {
+    /// The oldest Python version supported by pyo3-stub-gen, used when no version is specified
+    pub const MIN: Self = Self::new(3, 9);
+
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+
+    /// The lower bound of `project.requires-python`, e.g. `3.10` for `>=3.10, <4`.
+    ///
+    /// An exclusive bound without the patch version excludes the minor version, e.g. `3.10` for `>3.9`.
+    /// A bound below [PythonVersion::MIN], e.g. `>=3`, is raised to it.
+    /// `None` if the specifier has no lower bound.
+    pub fn from_requires_python(specifier: &str) -> Option<Self> {
+        specifier
+            .split(',')
+            .filter_map(|clause| {
+                let clause = clause.trim();
+                let (op, version) = ["~=", ">=", "==", ">"]
+                    .iter()
+                    .find_map(|op| Some((*op, clause.strip_prefix(op)?)))?;
+                let mut parts = version.trim().split('.');
+                let major = parts.next()?.parse().ok()?;
+                let minor: u8 = parts.next().map_or(Some(0), |minor| minor.parse().ok())?;
+                let has_patch = parts.next().is_some();
+                if op == ">" && !has_patch {
+                    return Some(Self::new(major, minor + 1));
+                }
+                Some(Self::new(major, minor))
+            })
+            .max()
+            .map(|version| version.max(Self::MIN))
+    }
+
+    /// `X | Y` is available (PEP 604)
+    pub fn has_union_operator(&self) -> bool {
+        *self >= Self::new(3, 10)
+    }
+
+    /// `type X = ...` and `class C[T]` are available (PEP 695)
+    pub fn has_type_parameter_syntax(&self) -> bool {
+        *self >= Self::new(3, 12)
+    }
+
+    /// Module providing the typing feature `name`, e.g. `typing_extensions` for `Self` before 3.11.
+    ///
+    /// `None` if `name` is not a feature backported by `typing_extensions`.
+    pub fn typing_module(&self, name: &str) -> Option<&'static str> {
+        let (since, module) = match name {
+            "ParamSpec" | "TypeAlias" => (Self::new(3, 10), "typing"),
+            "Self" | "Never" | "Required" | "NotRequired" => (Self::new(3, 11), "typing"),
+            "override" => (Self::new(3, 12), "typing"),
+            "deprecated" => (Self::new(3, 13), "warnings"),
+            "CapsuleType" => (Self::new(3, 13), "types"),
+            _ => return None,
+        };
+        Some(if *self >= since {
+            module
+        } else {
+            "typing_extensions"
+        })
+    }
+}
+
+impl Default for PythonVersion {
+    fn default() -> Self {
+        Self::MIN
+    }
+}
+
+impl fmt::Display for PythonVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for PythonVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (major, minor) = s
+            .split_once('.')
+            .with_context(|| format!("Python version must be `major.minor`, e.g. `3.10`: {s}"))?;
+        let version = Self::new(major.parse()?, minor.parse()?);
+        if version.major != 3 {
+            bail!("Unsupported Python version: {s}");
+        }
+        Ok(version)
+    }
+}
+
+impl TryFrom<String> for PythonVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<PythonVersion> for String {
+    fn from(version: PythonVersion) -> Self {
+        version.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(">=3.10", Some(PythonVersion::new(3, 10)) ; "lower_bound")]
+    #[test_case(">=3.9, <4", Some(PythonVersion::new(3, 9)) ; "range")]
+    #[test_case("~=3.11.2", Some(PythonVersion::new(3, 11)) ; "compatible")]
+    #[test_case("==3.12.*", Some(PythonVersion::new(3, 12)) ; "wildcard")]
+    #[test_case(">= 3", Some(PythonVersion::MIN) ; "major_only")]
+    #[test_case(">=3.7", Some(PythonVersion::MIN) ; "below_min")]
+    #[test_case(">3.9", Some(PythonVersion::new(3, 10)) ; "exclusive")]
+    #[test_case(">3.9.1", Some(PythonVersion::new(3, 9)) ; "exclusive_patch")]
+    #[test_case("<3.13", None ; "upper_bound_only")]
+    fn test_requires_python(specifier: &str, expected: Option<PythonVersion>) {
+        assert_eq!(PythonVersion::from_requires_python(specifier), expected);
+    }
+
+    #[test]
+    fn test_typing_module() {
+        let py310 = PythonVersion::new(3, 10);
+        let py313 = PythonVersion::new(3, 13);
+        assert_eq!(py310.typing_module("Self"), Some("typing_extensions"));
+        assert_eq!(py313.typing_module("Self"), Some("typing"));
+        assert_eq!(py310.typing_module("deprecated"), Some("typing_extensions"));
+        assert_eq!(py313.typing_module("deprecated"), Some("warnings"));
+        assert_eq!(py313.typing_module("Any"), None);
+        assert_eq!(
+            PythonVersion::MIN.typing_module("TypeAlias"),
+            Some("typing_extensions")
+        );
+        assert_eq!(py310.typing_module("TypeAlias"), Some("typing"));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{generate::*, type_info::*, TypeExpr, TypeInfo};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Definition of a type alias, i.e. `type Name = ...` since Python 3.12, or `Name: typing.TypeAlias = ...` before
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeAliasDef {
+    pub name: String,
+    pub doc: String,
+    pub r#type: TypeInfo,
+}
+
+impl From<&PyTypeAliasInfo> for TypeAliasDef {
+    fn from(info: &PyTypeAliasInfo) -> Self {
+        Self {
+            name: info.name.to_string(),
+            doc: info.doc.to_string(),
+            r#type: (info.r#type)(),
+        }
+    }
+}
+
+impl TypeAliasDef {
+    fn annotation() -> TypeInfo {
+        TypeExpr::qualified("typing", "TypeAlias").into()
+    }
+}
+
+impl Import for TypeAliasDef {
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = self.r#type.import(target);
+        if !target.has_type_parameter_syntax() {
+            import.extend(Self::annotation().import(target));
+        }
+        import
+    }
+}
+
+impl Render for TypeAliasDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        let r#type = ctx.display(&self.r#type);
+        if ctx.target.has_type_parameter_syntax() {
+            writeln!(f, "type {} = {}", self.name, r#type)?;
+        } else {
+            let annotation = Self::annotation();
+            writeln!(
+                f,
+                "{}: {} = {}",
+                self.name,
+                ctx.display(&annotation),
+                r#type
+            )?;
+        }
+        docstring::write_docstring(f, &self.doc, "")?;
+        writeln!(f)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypeAliasDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PyStubType;
+
+    fn imports_type_alias(alias: &TypeAliasDef, target: PythonVersion) -> bool {
+        alias.import(target).iter().any(
+            |import| matches!(import, ImportRef::Qualified(type_ref) if type_ref.name == "TypeAlias"),
+        )
+    }
+
+    #[test]
+    fn test_type_alias() {
+        let alias = TypeAliasDef {
+            name: "Number".to_string(),
+            doc: String::new(),
+            r#type: i64::type_input() | f64::type_input(),
+        };
+        let target = PythonVersion::new(3, 9);
+        assert_eq!(
+            RenderContext::new(target).display(&alias).to_string(),
+            "Number: typing_extensions.TypeAlias = typing.Union[builtins.int, builtins.float]\n\n"
+        );
+        assert!(alias.import(target).iter().any(|import| matches!(
+            import,
+            ImportRef::Qualified(type_ref)
+                if type_ref.name == "TypeAlias" && type_ref.module == "typing_extensions".into()
+        )));
+        let target = PythonVersion::new(3, 10);
+        assert_eq!(
+            RenderContext::new(target).display(&alias).to_string(),
+            "Number: typing.TypeAlias = builtins.int | builtins.float\n\n"
+        );
+        assert!(imports_type_alias(&alias, target));
+        let target = PythonVersion::new(3, 12);
+        assert_eq!(
+            RenderContext::new(target).display(&alias).to_string(),
+            "type Number = builtins.int | builtins.float\n\n"
+        );
+        assert!(!imports_type_alias(&alias, target));
+    }
+}
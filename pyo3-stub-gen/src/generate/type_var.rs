@@ -0,0 +1,224 @@
+use crate::{generate::*, type_info::*, TypeExpr, TypeInfo};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Definition of a module-level `typing.TypeVar` or `typing.ParamSpec`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeVarDef {
+    pub name: String,
+    pub kind: TypeVarKind,
+    /// `bound=...` of `TypeVar`
+    pub bound: Option<TypeInfo>,
+    /// Constraints of `TypeVar`, e.g. `typing.TypeVar("S", str, bytes)`
+    pub constraints: Vec<TypeInfo>,
+    /// Module declaring it, set for a type parameter of a class declared in another module,
+    /// which is imported by `from module import T` before Python 3.12
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+}
+
+impl From<&PyTypeVarInfo> for TypeVarDef {
+    fn from(info: &PyTypeVarInfo) -> Self {
+        Self {
+            name: info.name.to_string(),
+            kind: info.kind,
+            bound: info.bound.map(|f| f()),
+            constraints: info.constraints.iter().map(|f| f()).collect(),
+            module: None,
+        }
+    }
+}
+
+impl TypeVarDef {
+    /// `TypeVar` named `name` without bound and constraints, used until the declaration is found
+    pub(crate) fn undeclared(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: TypeVarKind::TypeVar,
+            bound: None,
+            constraints: Vec::new(),
+            module: None,
+        }
+    }
+
+    /// Type parameter of PEP 695 syntax, e.g. `T: builtins.float` or `**P`
+    pub(crate) fn type_param(&self, ctx: &RenderContext) -> String {
+        if self.kind == TypeVarKind::ParamSpec {
+            return format!("**{}", self.name);
+        }
+        if let Some(bound) = &self.bound {
+            return format!("{}: {}", self.name, ctx.display(bound));
+        }
+        if !self.constraints.is_empty() {
+            let constraints = self
+                .constraints
+                .iter()
+                .map(|constraint| ctx.display(constraint).to_string())
+                .collect::<Vec<_>>();
+            return format!("{}: ({})", self.name, constraints.join(", "));
+        }
+        self.name.clone()
+    }
+
+    /// Imports of the bound and the constraints
+    pub(crate) fn bound_import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = HashSet::new();
+        if let Some(bound) = &self.bound {
+            import.extend(bound.import(target));
+        }
+        for constraint in &self.constraints {
+            import.extend(constraint.import(target));
+        }
+        import
+    }
+
+    fn constructor(&self) -> TypeInfo {
+        match self.kind {
+            TypeVarKind::TypeVar => TypeExpr::qualified("typing", "TypeVar").into(),
+            // `typing.ParamSpec` is available since Python 3.10
+            TypeVarKind::ParamSpec => TypeExpr::qualified("typing_extensions", "ParamSpec").into(),
+        }
+    }
+}
+
+impl Import for TypeVarDef {
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = self.constructor().import(target);
+        import.extend(self.bound_import(target));
+        import
+    }
+}
+
+impl Render for TypeVarDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        write!(
+            f,
+            "{} = {}(\"{}\"",
+            self.name,
+            ctx.display(&self.constructor()),
+            self.name
+        )?;
+        for constraint in &self.constraints {
+            write!(f, ", {}", ctx.display(constraint))?;
+        }
+        if let Some(bound) = &self.bound {
+            write!(f, ", bound={}", ctx.display(bound))?;
+        }
+        writeln!(f, ")")
+    }
+}
+
+impl fmt::Display for TypeVarDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PyStubType;
+
+    #[test]
+    fn test_type_var() {
+        let type_var = |bound, constraints| TypeVarDef {
+            name: "T".to_string(),
+            kind: TypeVarKind::TypeVar,
+            bound,
+            constraints,
+            module: None,
+        };
+        assert_eq!(
+            type_var(None, vec![]).to_string(),
+            "T = typing.TypeVar(\"T\")\n"
+        );
+        assert_eq!(
+            type_var(Some(f64::type_output()), vec![]).to_string(),
+            "T = typing.TypeVar(\"T\", bound=builtins.float)\n"
+        );
+        assert_eq!(
+            type_var(
+                None,
+                vec![String::type_output(), TypeInfo::builtin("bytes")]
+            )
+            .to_string(),
+            "T = typing.TypeVar(\"T\", builtins.str, builtins.bytes)\n"
+        );
+    }
+
+    #[test]
+    fn test_param_spec() {
+        let param_spec = TypeVarDef {
+            name: "P".to_string(),
+            kind: TypeVarKind::ParamSpec,
+            bound: None,
+            constraints: vec![],
+            module: None,
+        };
+        let ctx = RenderContext::new(PythonVersion::new(3, 9));
+        assert_eq!(
+            ctx.display(&param_spec).to_string(),
+            "P = typing_extensions.ParamSpec(\"P\")\n"
+        );
+        let ctx = RenderContext::new(PythonVersion::new(3, 10));
+        assert_eq!(
+            ctx.display(&param_spec).to_string(),
+            "P = typing.ParamSpec(\"P\")\n"
+        );
+    }
+
+    fn imports_generic(class: &ClassDef, target: PythonVersion) -> bool {
+        class.import(target).iter().any(
+            |import| matches!(import, ImportRef::Qualified(type_ref) if type_ref.name == "Generic"),
+        )
+    }
+
+    #[test]
+    fn test_generic_class() {
+        let class = ClassDef {
+            name: "Store".to_string(),
+            doc: String::new(),
+            attrs: Vec::new(),
+            getter_setters: Default::default(),
+            methods: Default::default(),
+            bases: vec![TypeInfo::unqualified("Base")],
+            type_params: vec![
+                TypeVarDef {
+                    name: "N".to_string(),
+                    kind: TypeVarKind::TypeVar,
+                    bound: Some(f64::type_output()),
+                    constraints: vec![],
+                    module: None,
+                },
+                TypeVarDef {
+                    name: "S".to_string(),
+                    kind: TypeVarKind::TypeVar,
+                    bound: None,
+                    constraints: vec![String::type_output(), TypeInfo::builtin("bytes")],
+                    module: None,
+                },
+                TypeVarDef {
+                    name: "P".to_string(),
+                    kind: TypeVarKind::ParamSpec,
+                    bound: None,
+                    constraints: vec![],
+                    module: None,
+                },
+            ],
+            classes: Vec::new(),
+            match_args: None,
+        };
+        let target = PythonVersion::new(3, 11);
+        assert_eq!(
+            RenderContext::new(target).display(&class).to_string(),
+            "class Store(Base, typing.Generic[N, S, P]):\n    ...\n\n"
+        );
+        assert!(imports_generic(&class, target));
+        let target = PythonVersion::new(3, 12);
+        assert_eq!(
+            RenderContext::new(target).display(&class).to_string(),
+            "class Store[N: builtins.float, S: (builtins.str, builtins.bytes), **P](Base):\n    ...\n\n"
+        );
+        assert!(!imports_generic(&class, target));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{generate::*, type_info::*, TypeExpr, TypeInfo};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Definition of `typing.TypedDict`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypedDictDef {
+    pub name: String,
+    pub doc: String,
+    /// Whether the keys are required unless marked by `NotRequired`
+    pub total: bool,
+    pub fields: Vec<TypedDictFieldDef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypedDictFieldDef {
+    pub name: String,
+    pub r#type: TypeInfo,
+    pub required: bool,
+    pub doc: String,
+}
+
+impl From<&PyTypedDictInfo> for TypedDictDef {
+    fn from(info: &PyTypedDictInfo) -> Self {
+        Self {
+            name: info.name.to_string(),
+            doc: info.doc.to_string(),
+            total: info.total,
+            fields: info
+                .fields
+                .iter()
+                .map(|field| TypedDictFieldDef {
+                    name: field.name.to_string(),
+                    r#type: (field.r#type)(),
+                    required: field.required,
+                    doc: field.doc.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Python keywords, which cannot be a key of the class-based `TypedDict` syntax
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+impl TypedDictFieldDef {
+    /// Type annotation of the value, e.g. `typing.NotRequired[builtins.int]`
+    /// if the key may be missing in a `total=True` dict
+    pub fn annotation(&self, total: bool) -> TypeInfo {
+        let qualifier = match (total, self.required) {
+            (true, false) => "NotRequired",
+            (false, true) => "Required",
+            _ => return self.r#type.clone(),
+        };
+        TypeInfo::generic(
+            TypeExpr::qualified("typing_extensions", qualifier),
+            [self.r#type.clone()],
+        )
+    }
+
+    fn is_identifier(&self) -> bool {
+        !self.name.is_empty()
+            && !self.name.starts_with(|c: char| c.is_ascii_digit())
+            && self.name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && !KEYWORDS.contains(&self.name.as_str())
+    }
+}
+
+impl TypedDictDef {
+    fn base(&self) -> TypeInfo {
+        TypeExpr::qualified("typing", "TypedDict").into()
+    }
+
+    fn total(&self) -> &'static str {
+        if self.total {
+            "True"
+        } else {
+            "False"
+        }
+    }
+}
+
+impl Import for TypedDictDef {
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = self.base().import(target);
+        for field in &self.fields {
+            import.extend(field.annotation(self.total).import(target));
+        }
+        import
+    }
+}
+
+impl Render for TypedDictDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        let indent = indent();
+        // The functional syntax is required for keys which are not identifiers, e.g. `max-iter`
+        if !self.fields.iter().all(TypedDictFieldDef::is_identifier) {
+            writeln!(f, "{} = {}(", self.name, ctx.display(&self.base()))?;
+            writeln!(f, "{indent}\"{}\",", self.name)?;
+            writeln!(f, "{indent}{{")?;
+            for field in &self.fields {
+                writeln!(
+                    f,
+                    "{indent}{indent}\"{}\": {},",
+                    field.name,
+                    ctx.display(&field.annotation(self.total))
+                )?;
+            }
+            writeln!(f, "{indent}}},")?;
+            writeln!(f, "{indent}total={},", self.total())?;
+            writeln!(f, ")")?;
+            docstring::write_docstring(f, self.doc.trim(), "")?;
+            writeln!(f)?;
+            return Ok(());
+        }
+        write!(f, "class {}({}", self.name, ctx.display(&self.base()))?;
+        if !self.total {
+            write!(f, ", total=False")?;
+        }
+        writeln!(f, "):")?;
+        docstring::write_docstring(f, self.doc.trim(), indent)?;
+        for field in &self.fields {
+            writeln!(
+                f,
+                "{indent}{}: {}",
+                field.name,
+                ctx.display(&field.annotation(self.total))
+            )?;
+            docstring::write_docstring(f, &field.doc, indent)?;
+        }
+        if self.fields.is_empty() {
+            writeln!(f, "{indent}...")?;
+        }
+        writeln!(f)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypedDictDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PyStubType;
+
+    fn field(name: &str, r#type: TypeInfo, required: bool) -> TypedDictFieldDef {
+        TypedDictFieldDef {
+            name: name.to_string(),
+            r#type,
+            required,
+            doc: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_typed_dict() {
+        let mut typed_dict = TypedDictDef {
+            name: "Options".to_string(),
+            doc: "Solver options".to_string(),
+            total: true,
+            fields: vec![
+                field("max_iter", usize::type_input(), true),
+                field("tolerance", f64::type_input(), false),
+            ],
+        };
+        assert_eq!(
+            typed_dict.to_string(),
+            r#"class Options(typing.TypedDict):
+    r"""
+    Solver options
+    """
+    max_iter: builtins.int
+    tolerance: typing_extensions.NotRequired[builtins.float]
+
+"#
+        );
+
+        typed_dict.total = false;
+        typed_dict.fields[0].name = "max-iter".to_string();
+        assert_eq!(
+            typed_dict.to_string(),
+            r#"Options = typing.TypedDict(
+    "Options",
+    {
+        "max-iter": typing_extensions.Required[builtins.int],
+        "tolerance": builtins.float,
+    },
+    total=False,
+)
+r"""
+Solver options
+"""
+
+"#
+        );
+    }
+}
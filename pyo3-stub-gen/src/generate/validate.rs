@@ -0,0 +1,358 @@
+use crate::{generate::*, stub_type::ImportRef};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Result of [crate::StubInfo::validate], i.e. the problems in cross-references between the generated modules
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidationReport {
+    /// Imported types which are not registered in the referred module
+    pub dangling: Vec<DanglingRef>,
+    /// Class names registered more than once in a module
+    pub duplicate_classes: Vec<DuplicateClass>,
+    /// Names used by more than one kind of item, e.g. a class and a function, in a module
+    pub name_clashes: Vec<NameClash>,
+}
+
+/// A reference `from {target_module} import {name}` in `module` which does not exist
+#[derive(Debug, Clone, PartialEq)]
+pub struct DanglingRef {
+    /// Module where the reference appears
+    pub module: String,
+    /// Module where the type is expected to be defined
+    pub target_module: String,
+    pub name: String,
+    /// Modules where the type is actually registered, if any
+    pub registered_in: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateClass {
+    pub module: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameClash {
+    pub module: String,
+    pub name: String,
+    /// Kinds of the items using the name, e.g. `["class", "function"]`
+    pub kinds: Vec<&'static str>,
+}
+
+impl ValidationReport {
+    /// `true` if no problem is found
+    pub fn is_valid(&self) -> bool {
+        self.dangling.is_empty()
+            && self.duplicate_classes.is_empty()
+            && self.name_clashes.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            return writeln!(f, "No problem found in stub files.");
+        }
+        writeln!(f, "Invalid cross-references in stub files:")?;
+        for dangling in &self.dangling {
+            write!(
+                f,
+                "  dangling: `{}` refers `{}.{}`",
+                dangling.module, dangling.target_module, dangling.name
+            )?;
+            if dangling.registered_in.is_empty() {
+                writeln!(f, ", which is not registered")?;
+            } else {
+                writeln!(
+                    f,
+                    ", which is registered in {}",
+                    dangling.registered_in.join(", ")
+                )?;
+            }
+        }
+        for duplicate in &self.duplicate_classes {
+            writeln!(
+                f,
+                "  duplicate: class `{}` is defined more than once in `{}`",
+                duplicate.name, duplicate.module
+            )?;
+        }
+        for clash in &self.name_clashes {
+            writeln!(
+                f,
+                "  clash: `{}` is used by {} in `{}`",
+                clash.name,
+                clash.kinds.join(", "),
+                clash.module
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+pub(crate) fn validate(
+    modules: &BTreeMap<String, Module>,
+    target: PythonVersion,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    // Class, enum, typed dict, type alias, and type variable names registered in each module
+    let mut registered: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (module_name, module) in modules {
+        let mut kinds: BTreeMap<&str, Vec<&'static str>> = BTreeMap::new();
+        for class in &module.class {
+            kinds.entry(class.name.as_str()).or_default().push("class");
+        }
+        for enum_ in &module.enum_ {
+            kinds.entry(enum_.name.as_str()).or_default().push("class");
+        }
+        for typed_dict in &module.typed_dict {
+            kinds
+                .entry(typed_dict.name.as_str())
+                .or_default()
+                .push("class");
+        }
+        for type_alias in &module.type_alias {
+            kinds
+                .entry(type_alias.name.as_str())
+                .or_default()
+                .push("type alias");
+        }
+        for type_var in &module.type_var {
+            kinds
+                .entry(type_var.name.as_str())
+                .or_default()
+                .push("type variable");
+        }
+        for name in module.function.keys() {
+            kinds.entry(name.as_str()).or_default().push("function");
+        }
+        for name in module.variables.keys() {
+            kinds.entry(name.as_str()).or_default().push("variable");
+        }
+        for (name, kinds) in kinds {
+            let classes = kinds.iter().filter(|kind| **kind == "class").count();
+            if classes > 1 {
+                report.duplicate_classes.push(DuplicateClass {
+                    module: module_name.clone(),
+                    name: name.to_string(),
+                });
+            }
+            if classes > 0 || kinds.contains(&"type alias") || kinds.contains(&"type variable") {
+                registered.entry(module_name).or_default().insert(name);
+            }
+            let mut kinds = kinds;
+            kinds.dedup();
+            if kinds.len() > 1 {
+                report.name_clashes.push(NameClash {
+                    module: module_name.clone(),
+                    name: name.to_string(),
+                    kinds,
+                });
+            }
+        }
+    }
+
+    for (module_name, module) in modules {
+        let mut imports = BTreeSet::new();
+        for class in &module.class {
+            imports.extend(class.import(target));
+        }
+        for enum_ in &module.enum_ {
+            imports.extend(enum_.import(target));
+        }
+        for typed_dict in &module.typed_dict {
+            imports.extend(typed_dict.import(target));
+        }
+        for type_alias in &module.type_alias {
+            imports.extend(type_alias.import(target));
+        }
+        for type_var in &module.type_var {
+            imports.extend(type_var.import(target));
+        }
+        for function in module.function.values().flatten() {
+            imports.extend(function.import(target));
+        }
+        for variable in module.variables.values() {
+            imports.extend(variable.import(target));
+        }
+        // Type parameters of the classes must be declared in the same module, or imported from another module
+        for class in &module.class {
+            for type_param in &class.type_params {
+                if type_param.module.is_some()
+                    || module
+                        .type_var
+                        .iter()
+                        .any(|type_var| type_var.name == type_param.name)
+                {
+                    continue;
+                }
+                report.dangling.push(DanglingRef {
+                    module: module_name.clone(),
+                    target_module: module_name.clone(),
+                    name: type_param.name.clone(),
+                    registered_in: registered
+                        .iter()
+                        .filter(|(_, names)| names.contains(type_param.name.as_str()))
+                        .map(|(module, _)| module.to_string())
+                        .collect(),
+                });
+            }
+        }
+        for import in imports {
+            let ImportRef::Type(type_ref) = import else {
+                continue;
+            };
+            let target_module = type_ref.module.get().unwrap_or(&module.default_module_name);
+            if registered
+                .get(target_module)
+                .is_some_and(|names| names.contains(type_ref.name.as_str()))
+            {
+                continue;
+            }
+            // Other names can also be imported, e.g. a submodule or a variable imported in a `.pyi` fragment
+            if modules.get(target_module).is_some_and(|target| {
+                target.function.contains_key(&type_ref.name)
+                    || target.variables.contains_key(&type_ref.name)
+                    || target.submodules.contains(&type_ref.name)
+            }) {
+                continue;
+            }
+            let registered_in: Vec<String> = registered
+                .iter()
+                .filter(|(_, names)| names.contains(type_ref.name.as_str()))
+                .map(|(module, _)| module.to_string())
+                .collect();
+            // The type may be defined in another crate if neither the module nor the type is generated here
+            if !modules.contains_key(target_module) && registered_in.is_empty() {
+                continue;
+            }
+            report.dangling.push(DanglingRef {
+                module: module_name.clone(),
+                target_module: target_module.to_string(),
+                name: type_ref.name,
+                registered_in,
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TypeInfo;
+
+    fn class(name: &str) -> ClassDef {
+        ClassDef {
+            name: name.to_string(),
+            doc: String::new(),
+            attrs: Vec::new(),
+            getter_setters: Default::default(),
+            methods: Default::default(),
+            bases: Vec::new(),
+            type_params: Vec::new(),
+            classes: Vec::new(),
+            match_args: None,
+        }
+    }
+
+    fn variable(name: &str, type_: TypeInfo) -> VariableDef {
+        VariableDef {
+            name: name.to_string(),
+            type_,
+            default: None,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut pkg = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        pkg.class.push(class("A"));
+        pkg.class.push(class("A"));
+        pkg.class.push(class("B"));
+        pkg.variables
+            .insert("B".to_string(), variable("B", TypeInfo::builtin("int")));
+        // OK: `A` is registered in the default module `pkg`
+        pkg.variables.insert(
+            "a".to_string(),
+            variable("a", TypeInfo::locally_defined("A", Default::default())),
+        );
+        // Dangling: `C` is registered in `pkg.sub`, not in `pkg`
+        pkg.variables.insert(
+            "c".to_string(),
+            variable("c", TypeInfo::locally_defined("C", "pkg".into())),
+        );
+        // Dangling: `D` is not registered anywhere
+        pkg.variables.insert(
+            "d".to_string(),
+            variable("d", TypeInfo::locally_defined("D", "pkg.sub".into())),
+        );
+        // OK: the submodule `sub` and the variable `a` can also be imported, e.g. in a `.pyi` fragment
+        pkg.submodules.insert("sub".to_string());
+        pkg.variables.insert(
+            "s".to_string(),
+            variable("s", TypeInfo::locally_defined("sub", "pkg".into())),
+        );
+        pkg.variables.insert(
+            "t".to_string(),
+            variable("t", TypeInfo::locally_defined("a", "pkg".into())),
+        );
+        // OK: `other` is not generated here, and may be defined in another crate
+        pkg.variables.insert(
+            "e".to_string(),
+            variable("e", TypeInfo::locally_defined("E", "other".into())),
+        );
+
+        let mut sub = Module {
+            name: "pkg.sub".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        sub.class.push(class("C"));
+
+        let modules = BTreeMap::from([("pkg".to_string(), pkg), ("pkg.sub".to_string(), sub)]);
+        let report = validate(&modules, PythonVersion::MIN);
+        assert_eq!(
+            report.dangling,
+            vec![
+                DanglingRef {
+                    module: "pkg".to_string(),
+                    target_module: "pkg".to_string(),
+                    name: "C".to_string(),
+                    registered_in: vec!["pkg.sub".to_string()],
+                },
+                DanglingRef {
+                    module: "pkg".to_string(),
+                    target_module: "pkg.sub".to_string(),
+                    name: "D".to_string(),
+                    registered_in: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            report.duplicate_classes,
+            vec![DuplicateClass {
+                module: "pkg".to_string(),
+                name: "A".to_string(),
+            }]
+        );
+        assert_eq!(
+            report.name_clashes,
+            vec![NameClash {
+                module: "pkg".to_string(),
+                name: "B".to_string(),
+                kinds: vec!["class", "variable"],
+            }]
+        );
+        assert!(!report.is_valid());
+    }
+}
@@ -1,30 +1,48 @@
-use std::fmt;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
 
-use crate::{type_info::PyVariableInfo, TypeInfo};
+use crate::{
+    generate::{Import, PythonVersion, Render, RenderContext},
+    stub_type::ImportRef,
+    type_info::PyVariableInfo,
+    TypeInfo,
+};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VariableDef {
-    pub name: &'static str,
+    pub name: String,
     pub type_: TypeInfo,
     pub default: Option<String>,
 }
 
+impl Import for VariableDef {
+    fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        self.type_.import(target)
+    }
+}
+
 impl From<&PyVariableInfo> for VariableDef {
     fn from(info: &PyVariableInfo) -> Self {
         Self {
-            name: info.name,
+            name: info.name.to_string(),
             type_: (info.r#type)(),
             default: info.default.map(|f| f()),
         }
     }
 }
 
-impl fmt::Display for VariableDef {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}: {}", self.name, self.type_)?;
+impl Render for VariableDef {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        write!(f, "{}: {}", self.name, ctx.display(&self.type_))?;
         if let Some(default) = &self.default {
             write!(f, " = {default}")?;
         }
         Ok(())
     }
 }
+
+impl fmt::Display for VariableDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
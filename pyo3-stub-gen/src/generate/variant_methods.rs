@@ -3,7 +3,6 @@ use indexmap::IndexMap;
 use crate::generate::{Arg, MethodDef, MethodType};
 use crate::type_info::{PyComplexEnumInfo, VariantForm, VariantInfo};
 use crate::TypeInfo;
-use std::collections::HashSet;
 
 pub(super) fn get_variant_methods(
     enum_info: &PyComplexEnumInfo,
@@ -17,13 +16,10 @@ pub(super) fn get_variant_methods(
         .entry("__new__".to_string())
         .or_default()
         .push(MethodDef {
-            name: "__new__",
+            name: "__new__".to_string(),
             args: info.constr_args.iter().map(|a| a.into()).collect(),
-            r#return: TypeInfo {
-                name: full_class_name,
-                import: HashSet::new(),
-            },
-            doc: "",
+            r#return: TypeInfo::unqualified(&full_class_name),
+            doc: String::new(),
             r#type: MethodType::New,
             is_async: false,
             deprecated: None,
@@ -36,10 +32,10 @@ pub(super) fn get_variant_methods(
             .entry(len_name.to_string())
             .or_default()
             .push(MethodDef {
-                name: len_name,
+                name: len_name.to_string(),
                 args: Vec::new(),
                 r#return: TypeInfo::builtin("int"),
-                doc: "",
+                doc: String::new(),
                 r#type: MethodType::Instance,
                 is_async: false,
                 deprecated: None,
@@ -51,14 +47,14 @@ pub(super) fn get_variant_methods(
             .entry(getitem_name.to_string())
             .or_default()
             .push(MethodDef {
-                name: getitem_name,
+                name: getitem_name.to_string(),
                 args: vec![Arg {
-                    name: "key",
+                    name: "key".to_string(),
                     r#type: TypeInfo::builtin("int"),
                     signature: None,
                 }],
                 r#return: TypeInfo::any(),
-                doc: "",
+                doc: String::new(),
                 r#type: MethodType::Instance,
                 is_async: false,
                 deprecated: None,
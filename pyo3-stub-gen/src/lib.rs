@@ -60,6 +60,9 @@
 //!         // Base classes
 //!         bases: &[],
 //!
+//!         // Type parameters by `#[gen_stub(generic = (T, ...))]`
+//!         type_params: &[],
+//!
 //!         // Decorated with `#[pyclass(eq, ord)]`
 //!         has_eq: false,
 //!         has_ord: false,
@@ -137,10 +140,10 @@
 //! use pyo3_stub_gen::{TypeInfo, generate::*};
 //!
 //! let method = MethodDef {
-//!     name: "foo",
-//!     args: vec![Arg { name: "x", r#type: TypeInfo::builtin("int"), signature: None, }],
+//!     name: "foo".to_string(),
+//!     args: vec![Arg { name: "x".to_string(), r#type: TypeInfo::builtin("int"), signature: None, }],
 //!     r#return: TypeInfo::builtin("int"),
-//!     doc: "This is a foo method.",
+//!     doc: "This is a foo method.".to_string(),
 //!     r#type: MethodType::Instance,
 //!     deprecated: None,
 //!     is_async: false,
@@ -175,15 +178,19 @@ pub mod pyproject;
 pub mod rule_name;
 mod stub_type;
 pub mod type_info;
+pub mod typed;
 pub mod util;
 
 pub use generate::StubInfo;
-pub use stub_type::{PyStubType, TypeInfo};
+pub use stub_type::{ImportRef, LiteralValue, ModuleRef, PyStubType, TypeExpr, TypeInfo, TypeRef};
 
 pub type Result<T> = anyhow::Result<T>;
 
 /// Create a function to initialize [StubInfo] from `pyproject.toml` in `CARGO_MANIFEST_DIR`.
 ///
+/// setuptools-rust projects whose `pyproject.toml` is in an ancestor directory,
+/// and crates without `pyproject.toml` using `[package.metadata.maturin]` in `Cargo.toml` are also supported.
+/// See [pyproject::PyProject::from_manifest_dir] for details.
 /// If `pyproject.toml` is in another place, you need to create a function to call [StubInfo::from_pyproject_toml] manually.
 /// This must be placed in your PyO3 library crate, i.e. same crate where [inventory::submit]ted,
 /// not in `gen_stub` executables due to [inventory] mechanism.
@@ -194,7 +201,7 @@ macro_rules! define_stub_info_gatherer {
         /// Auto-generated function to gather information to generate stub files
         pub fn $function_name() -> $crate::Result<$crate::StubInfo> {
             let manifest_dir: &::std::path::Path = env!("CARGO_MANIFEST_DIR").as_ref();
-            $crate::StubInfo::from_pyproject_toml(manifest_dir.join("pyproject.toml"))
+            $crate::StubInfo::from_manifest_dir(manifest_dir)
         }
     };
 }
@@ -264,5 +271,60 @@ macro_rules! module_variable {
     };
 }
 
+/// Add module-level `typing.TypeVar`, the first argument `module_typevar!` receives is the full module name,
+/// and the second argument is the name of the type variable.
+/// The bound or the constraints of the type variable can be given by `bound = Type` or `constraints = (Type, ...)`.
+/// ```rust
+/// pyo3_stub_gen::module_typevar!("module.name", "T");
+/// pyo3_stub_gen::module_typevar!("module.name", "N", bound = f64);
+/// pyo3_stub_gen::module_typevar!("module.name", "S", constraints = (String, i64));
+/// ```
+///
+/// The type variable can be used as `#[gen_stub(generic = (T,))]` of `#[gen_stub_pyclass]`,
+/// and in `#[gen_stub(override_type(type_repr = "T"))]` of the methods.
+#[macro_export]
+macro_rules! module_typevar {
+    (@submit $module:expr, $name:expr, $bound:expr, $constraints:expr) => {
+        $crate::inventory::submit! {
+            $crate::type_info::PyTypeVarInfo {
+                name: $name,
+                module: $module,
+                kind: $crate::type_info::TypeVarKind::TypeVar,
+                bound: $bound,
+                constraints: $constraints,
+            }
+        }
+    };
+    ($module:expr, $name:expr) => {
+        $crate::module_typevar!(@submit $module, $name, None, &[]);
+    };
+    ($module:expr, $name:expr, bound = $bound:ty) => {
+        $crate::module_typevar!(@submit $module, $name, Some(<$bound as $crate::PyStubType>::type_output), &[]);
+    };
+    ($module:expr, $name:expr, constraints = ($($constraint:ty),+ $(,)?)) => {
+        $crate::module_typevar!(@submit $module, $name, None, &[$(<$constraint as $crate::PyStubType>::type_output),+]);
+    };
+}
+
+/// Add module-level `typing.ParamSpec`, the first argument `module_paramspec!` receives is the full module name,
+/// and the second argument is the name of the parameter specification.
+/// ```rust
+/// pyo3_stub_gen::module_paramspec!("module.name", "P");
+/// ```
+#[macro_export]
+macro_rules! module_paramspec {
+    ($module:expr, $name:expr) => {
+        $crate::inventory::submit! {
+            $crate::type_info::PyTypeVarInfo {
+                name: $name,
+                module: $module,
+                kind: $crate::type_info::TypeVarKind::ParamSpec,
+                bound: None,
+                constraints: &[],
+            }
+        }
+    };
+}
+
 #[doc = include_str!("../README.md")]
 mod readme {}
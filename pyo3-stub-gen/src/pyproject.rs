@@ -1,4 +1,6 @@
-//! `pyproject.toml` parser for reading `[tool.maturin]` configuration.
+//! `pyproject.toml` parser for reading `[tool.maturin]`, `[tool.setuptools-rust]` and `[tool.pyo3-stub-gen]` configuration.
+//!
+//! For crates without `pyproject.toml`, [PyProject::from_cargo_toml] reads `[package.metadata.maturin]` in `Cargo.toml` instead.
 //!
 //! ```
 //! use pyo3_stub_gen::pyproject::PyProject;
@@ -10,9 +12,10 @@
 //! ).unwrap();
 //! ```
 
+use crate::generate::{OrphanMethodsPolicy, PyTyped, PythonVersion, TypeNameStyle};
 use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
-use std::{fs, path::*};
+use std::{collections::BTreeMap, fs, path::*};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PyProject {
@@ -21,6 +24,9 @@ pub struct PyProject {
 
     #[serde(skip)]
     toml_path: PathBuf,
+    /// Directory of `Cargo.toml` of the PyO3 crate, used to select the setuptools-rust extension
+    #[serde(skip)]
+    manifest_dir: Option<PathBuf>,
 }
 
 impl PyProject {
@@ -34,6 +40,83 @@ impl PyProject {
         Ok(out)
     }
 
+    /// Read `[package]` and `[package.metadata.maturin]` in `Cargo.toml` for crates without `pyproject.toml`.
+    ///
+    /// The package name is used as the project name,
+    /// and `name` and `python-source` in `[package.metadata.maturin]` are used as `module-name` and `python-source`.
+    pub fn from_cargo_toml(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if path.file_name() != Some("Cargo.toml".as_ref()) {
+            bail!("{} is not a Cargo.toml", path.display())
+        }
+        let manifest: CargoManifest = toml::de::from_str(&fs::read_to_string(path)?)?;
+        let maturin = manifest
+            .package
+            .metadata
+            .and_then(|metadata| metadata.maturin)
+            .unwrap_or_default();
+        Ok(PyProject {
+            project: Project {
+                name: manifest.package.name,
+                requires_python: None,
+            },
+            tool: Some(Tool {
+                maturin: Some(Maturin {
+                    python_source: maturin.python_source,
+                    module_name: maturin.name,
+                }),
+                setuptools: None,
+                setuptools_rust: None,
+                pyo3_stub_gen: None,
+            }),
+            toml_path: path.to_path_buf(),
+            manifest_dir: path.parent().map(Path::to_path_buf),
+        })
+    }
+
+    /// Find the project definition of the PyO3 crate in `manifest_dir`, i.e. `CARGO_MANIFEST_DIR`.
+    ///
+    /// This looks up the following in order:
+    ///
+    /// 1. `pyproject.toml` in `manifest_dir`
+    /// 2. `pyproject.toml` in an ancestor directory whose `[tool.setuptools-rust]` ext-modules refers `Cargo.toml` in `manifest_dir`
+    /// 3. `Cargo.toml` in `manifest_dir`, see [PyProject::from_cargo_toml]
+    ///
+    /// An ancestor `pyproject.toml` which cannot be parsed is skipped with a warning.
+    pub fn from_manifest_dir(manifest_dir: impl AsRef<Path>) -> Result<Self> {
+        let manifest_dir = manifest_dir.as_ref();
+        let pyproject_toml = manifest_dir.join("pyproject.toml");
+        if pyproject_toml.exists() {
+            let mut out = Self::parse_toml(pyproject_toml)?;
+            out.manifest_dir = Some(manifest_dir.to_path_buf());
+            return Ok(out);
+        }
+        for dir in manifest_dir.ancestors().skip(1) {
+            let pyproject_toml = dir.join("pyproject.toml");
+            if !pyproject_toml.exists() {
+                continue;
+            }
+            // An unrelated pyproject.toml may not be a valid Python project, e.g. a workspace root
+            let mut out = match Self::parse_toml(&pyproject_toml) {
+                Ok(out) => out,
+                Err(err) => {
+                    log::warn!(
+                        "Skip {path} which cannot be parsed: {err:#}",
+                        path = pyproject_toml.display()
+                    );
+                    continue;
+                }
+            };
+            out.manifest_dir = Some(manifest_dir.to_path_buf());
+            if out.setuptools_rust_extension().is_some() {
+                return Ok(out);
+            }
+        }
+        Self::from_cargo_toml(manifest_dir.join("Cargo.toml"))
+    }
+
+    /// Module name of the extension, i.e. `tool.maturin.module-name`,
+    /// the `target` of the setuptools-rust extension, or `project.name` in this order.
     pub fn module_name(&self) -> &str {
         if let Some(tool) = &self.tool {
             if let Some(maturin) = &tool.maturin {
@@ -42,34 +125,104 @@ impl PyProject {
                 }
             }
         }
+        if let Some(extension) = self.setuptools_rust_extension() {
+            return &extension.target;
+        }
         &self.project.name
     }
 
+    /// The setuptools-rust extension built from this crate.
+    ///
+    /// This is the entry of `tool.setuptools-rust.ext-modules` whose `path` refers `Cargo.toml` of this crate.
+    /// If the crate directory is unknown, i.e. this is not created by [PyProject::from_manifest_dir],
+    /// the first entry is returned.
+    pub fn setuptools_rust_extension(&self) -> Option<&RustExtension> {
+        let ext_modules = &self.tool.as_ref()?.setuptools_rust.as_ref()?.ext_modules;
+        let Some(manifest_dir) = &self.manifest_dir else {
+            return ext_modules.first();
+        };
+        let manifest = manifest_dir.join("Cargo.toml");
+        ext_modules.iter().find(|extension| {
+            let path = self.resolve(extension.path.as_deref().unwrap_or("Cargo.toml"));
+            same_file(&path, &manifest)
+        })
+    }
+
     /// Return `tool.maturin.python_source` if it exists, which means the project is a mixed Rust/Python project.
+    ///
+    /// For setuptools-rust projects, this returns the root package directory `tool.setuptools.package-dir[""]`
+    /// or the directory of `pyproject.toml`.
     pub fn python_source(&self) -> Option<PathBuf> {
         if let Some(tool) = &self.tool {
             if let Some(maturin) = &tool.maturin {
                 if let Some(python_source) = &maturin.python_source {
-                    if let Some(base) = self.toml_path.parent() {
-                        return Some(base.join(python_source));
-                    } else {
-                        return Some(PathBuf::from(python_source));
-                    }
+                    return Some(self.resolve(python_source));
                 }
             }
         }
+        if self.setuptools_rust_extension().is_some() {
+            let package_dir = self
+                .tool
+                .as_ref()
+                .and_then(|tool| tool.setuptools.as_ref())
+                .and_then(|setuptools| setuptools.package_dir.get(""));
+            return Some(match package_dir {
+                Some(package_dir) => self.resolve(package_dir),
+                None => self.resolve(""),
+            });
+        }
         None
     }
+
+    /// Return `[tool.pyo3-stub-gen]` table if it exists.
+    pub fn stub_gen(&self) -> Option<&Pyo3StubGen> {
+        self.tool.as_ref()?.pyo3_stub_gen.as_ref()
+    }
+
+    /// Return `tool.pyo3-stub-gen.output-dir` if it exists.
+    pub fn output_dir(&self) -> Option<PathBuf> {
+        let output_dir = self.stub_gen()?.output_dir.as_ref()?;
+        Some(self.resolve(output_dir))
+    }
+
+    /// Return `tool.pyo3-stub-gen.fragments` with the paths resolved.
+    pub fn fragments(&self) -> BTreeMap<String, PathBuf> {
+        self.stub_gen()
+            .map(|config| {
+                config
+                    .fragments
+                    .iter()
+                    .map(|(module, path)| (module.clone(), self.resolve(path)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Resolve a path relative to the directory of `pyproject.toml`
+    fn resolve(&self, path: &str) -> PathBuf {
+        if let Some(base) = self.toml_path.parent() {
+            base.join(path)
+        } else {
+            PathBuf::from(path)
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Project {
     pub name: String,
+    #[serde(rename = "requires-python")]
+    pub requires_python: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tool {
     pub maturin: Option<Maturin>,
+    pub setuptools: Option<Setuptools>,
+    #[serde(rename = "setuptools-rust")]
+    pub setuptools_rust: Option<SetuptoolsRust>,
+    #[serde(rename = "pyo3-stub-gen")]
+    pub pyo3_stub_gen: Option<Pyo3StubGen>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -79,3 +232,243 @@ pub struct Maturin {
     #[serde(rename = "module-name")]
     pub module_name: Option<String>,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Setuptools {
+    #[serde(rename = "package-dir", default)]
+    pub package_dir: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetuptoolsRust {
+    #[serde(rename = "ext-modules", default)]
+    pub ext_modules: Vec<RustExtension>,
+}
+
+/// An entry of `tool.setuptools-rust.ext-modules`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RustExtension {
+    /// Full module name of the extension, e.g. `pkg._native`
+    pub target: String,
+    /// Path to `Cargo.toml` relative to `pyproject.toml`, `Cargo.toml` if not specified
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoManifest {
+    package: CargoPackage,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoPackage {
+    name: String,
+    metadata: Option<CargoMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoMetadata {
+    maturin: Option<CargoMaturin>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CargoMaturin {
+    name: Option<String>,
+    #[serde(rename = "python-source")]
+    python_source: Option<String>,
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// `[tool.pyo3-stub-gen]` table
+///
+/// ```toml
+/// [tool.pyo3-stub-gen]
+/// output-dir = "stubs"
+/// header = "Copyright (c) 2025 Example"
+/// exclude-modules = ["my_module.internal"]
+/// py-typed = "partial"
+/// orphan-methods = "skip"
+///
+/// [tool.pyo3-stub-gen.fragments]
+/// "my_module._native" = "stubs/native.pyi"
+/// ```
+///
+/// Unknown keys are rejected to catch typos.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pyo3StubGen {
+    /// Directory where the stub files are written, relative to `pyproject.toml`.
+    /// This overrides `tool.maturin.python-source`.
+    #[serde(rename = "output-dir")]
+    pub output_dir: Option<String>,
+    /// Text written as comments at the top of each stub file
+    pub header: Option<String>,
+    /// Generate only these modules and their submodules if not empty
+    #[serde(rename = "include-modules", default)]
+    pub include_modules: Vec<String>,
+    /// Do not generate these modules and their submodules
+    #[serde(rename = "exclude-modules", default)]
+    pub exclude_modules: Vec<String>,
+    /// Write `py.typed` marker into each top-level package, `"complete"` or `"partial"`
+    #[serde(rename = "py-typed")]
+    pub py_typed: Option<PyTyped>,
+    /// How to handle `#[gen_stub_pymethods]` for unregistered types, `"error"` (default), `"placeholder"` or `"skip"`
+    #[serde(rename = "orphan-methods")]
+    pub orphan_methods: Option<OrphanMethodsPolicy>,
+    /// Companion `.pyi` fragments merged into the modules, relative to `pyproject.toml`.
+    /// Only the fragments listed here are merged.
+    #[serde(default)]
+    pub fragments: BTreeMap<String, String>,
+    /// How the type names are printed, `"qualified"` (default) or `"short"`
+    #[serde(rename = "type-names")]
+    pub type_names: Option<TypeNameStyle>,
+    /// Python version targeted by the stub files, e.g. `"3.10"`.
+    /// The lower bound of `project.requires-python` is used if not specified.
+    #[serde(rename = "target-version")]
+    pub target_version: Option<PythonVersion>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pyo3_stub_gen_table() {
+        let pyproject: PyProject = toml::de::from_str(
+            r#"
+            [project]
+            name = "my_package"
+
+            [tool.pyo3-stub-gen]
+            output-dir = "stubs"
+            header = "Copyright"
+            include-modules = ["my_package"]
+            exclude-modules = ["my_package.internal"]
+            py-typed = "partial"
+            orphan-methods = "placeholder"
+            type-names = "short"
+            target-version = "3.12"
+
+            [tool.pyo3-stub-gen.fragments]
+            "my_package._native" = "stubs/native.pyi"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            pyproject.stub_gen(),
+            Some(&Pyo3StubGen {
+                output_dir: Some("stubs".to_string()),
+                header: Some("Copyright".to_string()),
+                include_modules: vec!["my_package".to_string()],
+                exclude_modules: vec!["my_package.internal".to_string()],
+                py_typed: Some(PyTyped::Partial),
+                orphan_methods: Some(OrphanMethodsPolicy::Placeholder),
+                type_names: Some(TypeNameStyle::Short),
+                target_version: Some(PythonVersion::new(3, 12)),
+                fragments: BTreeMap::from([(
+                    "my_package._native".to_string(),
+                    "stubs/native.pyi".to_string()
+                )]),
+            })
+        );
+        assert_eq!(pyproject.output_dir(), Some(PathBuf::from("stubs")));
+        assert_eq!(
+            pyproject.fragments(),
+            BTreeMap::from([(
+                "my_package._native".to_string(),
+                PathBuf::from("stubs/native.pyi")
+            )])
+        );
+    }
+
+    #[test]
+    fn test_pyo3_stub_gen_unknown_key() {
+        let err = toml::de::from_str::<PyProject>(
+            r#"
+            [project]
+            name = "my_package"
+
+            [tool.pyo3-stub-gen]
+            output_dir = "stubs"
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown field `output_dir`"));
+    }
+
+    #[test]
+    fn test_setuptools_rust() {
+        let pyproject: PyProject = toml::de::from_str(
+            r#"
+            [project]
+            name = "my-package"
+
+            [tool.setuptools]
+            package-dir = { "" = "src" }
+
+            [[tool.setuptools-rust.ext-modules]]
+            target = "my_package._native"
+            path = "rust/Cargo.toml"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(pyproject.module_name(), "my_package._native");
+        assert_eq!(pyproject.python_source(), Some(PathBuf::from("src")));
+    }
+
+    #[test]
+    fn test_from_manifest_dir() -> Result<()> {
+        let root =
+            std::env::temp_dir().join(format!("pyo3-stub-gen-manifest-dir-{}", std::process::id()));
+        if root.exists() {
+            fs::remove_dir_all(&root)?;
+        }
+        let manifest_dir = root.join("rust");
+        fs::create_dir_all(&manifest_dir)?;
+        fs::write(
+            manifest_dir.join("Cargo.toml"),
+            r#"
+            [package]
+            name = "my-crate"
+
+            [package.metadata.maturin]
+            name = "my_package._native"
+            python-source = "python"
+            "#,
+        )?;
+
+        // Cargo.toml only
+        let pyproject = PyProject::from_manifest_dir(&manifest_dir)?;
+        assert_eq!(pyproject.project.name, "my-crate");
+        assert_eq!(pyproject.module_name(), "my_package._native");
+        assert_eq!(pyproject.python_source(), Some(manifest_dir.join("python")));
+
+        // setuptools-rust pyproject.toml in the parent directory
+        fs::write(
+            root.join("pyproject.toml"),
+            r#"
+            [project]
+            name = "my-package"
+
+            [[tool.setuptools-rust.ext-modules]]
+            target = "my_package._other"
+            path = "other/Cargo.toml"
+
+            [[tool.setuptools-rust.ext-modules]]
+            target = "my_package._rust"
+            path = "rust/Cargo.toml"
+            "#,
+        )?;
+        let pyproject = PyProject::from_manifest_dir(&manifest_dir)?;
+        assert_eq!(pyproject.module_name(), "my_package._rust");
+        assert_eq!(pyproject.python_source(), Some(root.clone()));
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
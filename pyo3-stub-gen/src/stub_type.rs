@@ -1,5 +1,6 @@
 mod builtins;
 mod collections;
+mod expr;
 mod pyo3;
 
 #[cfg(feature = "numpy")]
@@ -11,17 +12,22 @@ mod either;
 #[cfg(feature = "rust_decimal")]
 mod rust_decimal;
 
+pub use expr::*;
+
 use maplit::hashset;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::{collections::HashSet, fmt, ops};
 
 /// Indicates what to import.
 /// Module: The purpose is to import the entire module(eg import builtins).
 /// Type: The purpose is to import the types in the module(eg from moduleX import typeX).
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Qualified: A name used as `module.name`, which is imported either way depending on [crate::generate::TypeNameStyle].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ImportRef {
     Module(ModuleRef),
     Type(TypeRef),
+    Qualified(TypeRef),
 }
 
 impl From<&str> for ImportRef {
@@ -38,16 +44,23 @@ impl PartialOrd for ImportRef {
 
 impl Ord for ImportRef {
     fn cmp(&self, other: &Self) -> Ordering {
+        fn rank(import_ref: &ImportRef) -> u8 {
+            match import_ref {
+                ImportRef::Type(_) => 0,
+                ImportRef::Qualified(_) => 1,
+                ImportRef::Module(_) => 2,
+            }
+        }
         match (self, other) {
             (ImportRef::Module(a), ImportRef::Module(b)) => a.get().cmp(&b.get()),
-            (ImportRef::Type(a), ImportRef::Type(b)) => a.cmp(b),
-            (ImportRef::Module(_), ImportRef::Type(_)) => Ordering::Greater,
-            (ImportRef::Type(_), ImportRef::Module(_)) => Ordering::Less,
+            (ImportRef::Type(a), ImportRef::Type(b))
+            | (ImportRef::Qualified(a), ImportRef::Qualified(b)) => a.cmp(b),
+            _ => rank(self).cmp(&rank(other)),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Hash, Serialize, Deserialize)]
 pub enum ModuleRef {
     Named(String),
 
@@ -81,7 +94,7 @@ impl From<&str> for ModuleRef {
 /// Indicates the type of import(eg class enum).
 /// from module import type.
 /// name, type name. module, module name(which type defined).
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Hash, Serialize, Deserialize)]
 pub struct TypeRef {
     pub module: ModuleRef,
     pub name: String,
@@ -97,95 +110,173 @@ impl TypeRef {
 }
 
 /// Type information for creating Python stub files annotated by [PyStubType] trait.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TypeInfo {
     /// The Python type name.
-    pub name: String,
+    pub name: TypeExpr,
 
-    /// Python modules must be imported in the stub file.
+    /// Python modules must be imported in the stub file in addition to the ones used in `name`.
     ///
-    /// For example, when `name` is `typing.Sequence[int]`, `import` should contain `typing`.
+    /// For example, when `name` is [TypeExpr::Raw] `typing.Sequence[int]`, `import` should contain `typing`.
     /// This makes it possible to use user-defined types in the stub file.
+    #[serde(serialize_with = "serialize_sorted")]
     pub import: HashSet<ImportRef>,
 }
 
+/// Serialize [TypeInfo::import] in a stable order
+fn serialize_sorted<S: serde::Serializer>(
+    import: &HashSet<ImportRef>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut import: Vec<_> = import.iter().collect();
+    import.sort();
+    import.serialize(serializer)
+}
+
+impl crate::generate::Render for TypeInfo {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &crate::generate::RenderContext) -> fmt::Result {
+        self.name.render(f, ctx)
+    }
+}
+
 impl fmt::Display for TypeInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.name)
     }
 }
 
+impl From<TypeExpr> for TypeInfo {
+    fn from(name: TypeExpr) -> Self {
+        Self {
+            name,
+            import: HashSet::new(),
+        }
+    }
+}
+
+/// `true` for a Python identifier, e.g. `int` but not `dict[str, str]`
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
 impl TypeInfo {
     /// A `None` type annotation.
     pub fn none() -> Self {
         // NOTE: since 3.10, NoneType is provided from types module,
         // but there is no corresponding definitions prior to 3.10.
-        Self {
-            name: "None".to_string(),
-            import: HashSet::new(),
-        }
+        TypeExpr::None.into()
     }
 
     /// A `typing.Any` type annotation.
     pub fn any() -> Self {
+        TypeExpr::qualified("typing", "Any").into()
+    }
+
+    /// A generic type annotation `base[args, ...]`, e.g. `typing.Sequence[builtins.int]`:
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{TypeExpr, TypeInfo};
+    /// TypeInfo::generic(TypeExpr::qualified("typing", "Sequence"), [TypeInfo::builtin("int")]);
+    /// ```
+    pub fn generic(base: TypeExpr, args: impl IntoIterator<Item = TypeInfo>) -> Self {
+        let mut import = HashSet::new();
+        let args = args
+            .into_iter()
+            .map(|arg| {
+                import.extend(arg.import);
+                arg.name
+            })
+            .collect();
         Self {
-            name: "typing.Any".to_string(),
-            import: hashset! { "typing".into() },
+            name: TypeExpr::Generic {
+                base: Box::new(base),
+                args,
+            },
+            import,
         }
     }
 
-    /// A `list[Type]` type annotation.
-    pub fn list_of<T: PyStubType>() -> Self {
-        let TypeInfo { name, mut import } = T::type_output();
-        import.insert("builtins".into());
-        TypeInfo {
-            name: format!("builtins.list[{name}]"),
+    /// A union type annotation `A | B | ...`, normalized by [TypeExpr::union]
+    pub fn union(members: impl IntoIterator<Item = TypeInfo>) -> Self {
+        let mut import = HashSet::new();
+        let name = TypeExpr::union(members.into_iter().map(|member| {
+            import.extend(member.import);
+            member.name
+        }));
+        Self { name, import }
+    }
+
+    /// A `typing.Optional[Type]` type annotation, i.e. the union of `Type` and `None`.
+    pub fn optional(inner: TypeInfo) -> Self {
+        Self::union([inner, Self::none()])
+    }
+
+    /// A `typing.Literal[values, ...]` type annotation of strings, integers, or booleans:
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{LiteralValue, TypeInfo};
+    /// TypeInfo::literal(["fast", "accurate"]);
+    /// TypeInfo::literal([LiteralValue::from("auto"), LiteralValue::from(0)]);
+    /// ```
+    pub fn literal<V: Into<LiteralValue>>(values: impl IntoIterator<Item = V>) -> Self {
+        TypeExpr::Literal(values.into_iter().map(Into::into).collect()).into()
+    }
+
+    /// A `collections.abc.Callable[[args, ...], ret]` type annotation. `None` for `args` means any arguments, i.e. `...`.
+    pub fn callable(args: Option<Vec<TypeInfo>>, ret: TypeInfo) -> Self {
+        let mut import = ret.import;
+        let args = args.map(|args| {
+            args.into_iter()
+                .map(|arg| {
+                    import.extend(arg.import);
+                    arg.name
+                })
+                .collect()
+        });
+        Self {
+            name: TypeExpr::Callable {
+                args,
+                ret: Box::new(ret.name),
+            },
             import,
         }
     }
 
+    /// A `list[Type]` type annotation.
+    pub fn list_of<T: PyStubType>() -> Self {
+        Self::generic(TypeExpr::qualified("builtins", "list"), [T::type_output()])
+    }
+
     /// A `set[Type]` type annotation.
     pub fn set_of<T: PyStubType>() -> Self {
-        let TypeInfo { name, mut import } = T::type_output();
-        import.insert("builtins".into());
-        TypeInfo {
-            name: format!("builtins.set[{name}]"),
-            import,
-        }
+        Self::generic(TypeExpr::qualified("builtins", "set"), [T::type_output()])
     }
 
-    /// A `dict[Type]` type annotation.
+    /// A `dict[Key, Value]` type annotation.
     pub fn dict_of<K: PyStubType, V: PyStubType>() -> Self {
-        let TypeInfo {
-            name: name_k,
-            mut import,
-        } = K::type_output();
-        let TypeInfo {
-            name: name_v,
-            import: import_v,
-        } = V::type_output();
-        import.extend(import_v);
-        import.insert("builtins".into());
-        TypeInfo {
-            name: format!("builtins.set[{name_k}, {name_v}]"),
-            import,
-        }
+        Self::generic(
+            TypeExpr::qualified("builtins", "dict"),
+            [K::type_output(), V::type_output()],
+        )
     }
 
     /// A type annotation of a built-in type provided from `builtins` module, such as `int`, `str`, or `float`. Generic builtin types are also possible, such as `dict[str, str]`.
     pub fn builtin(name: &str) -> Self {
-        Self {
-            name: format!("builtins.{name}"),
-            import: hashset! { "builtins".into() },
+        if is_identifier(name) {
+            TypeExpr::qualified("builtins", name).into()
+        } else {
+            Self {
+                name: TypeExpr::Raw(format!("builtins.{name}")),
+                import: hashset! { "builtins".into() },
+            }
         }
     }
 
     /// Unqualified type.
     pub fn unqualified(name: &str) -> Self {
-        Self {
-            name: name.to_string(),
-            import: hashset! {},
-        }
+        TypeExpr::Raw(name.to_string()).into()
     }
 
     /// A type annotation of a type that must be imported. The type name must be qualified with the module name:
@@ -194,10 +285,19 @@ impl TypeInfo {
     /// pyo3_stub_gen::TypeInfo::with_module("pathlib.Path", "pathlib".into());
     /// ```
     pub fn with_module(name: &str, module: ModuleRef) -> Self {
+        if let Some(module_name) = module.get() {
+            if let Some(type_name) = name
+                .strip_prefix(module_name)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .filter(|type_name| is_identifier(type_name))
+            {
+                return TypeExpr::qualified(module_name, type_name).into();
+            }
+        }
         let mut import = HashSet::new();
         import.insert(ImportRef::Module(module));
         Self {
-            name: name.to_string(),
+            name: TypeExpr::Raw(name.to_string()),
             import,
         }
     }
@@ -213,26 +313,23 @@ impl TypeInfo {
     /// pyo3_stub_gen::TypeInfo::locally_defined("A", "submod1".into());
     /// ```
     pub fn locally_defined(type_name: &str, module: ModuleRef) -> Self {
-        let mut import = HashSet::new();
-        let type_ref = TypeRef::new(module, type_name.to_string());
-        import.insert(ImportRef::Type(type_ref));
+        TypeExpr::Local(TypeRef::new(module, type_name.to_string())).into()
+    }
+}
 
-        Self {
-            name: type_name.to_string(),
-            import,
-        }
+impl crate::generate::Import for TypeInfo {
+    fn import(&self, target: crate::generate::PythonVersion) -> HashSet<ImportRef> {
+        let mut import = self.name.import(target);
+        import.extend(self.import.iter().cloned());
+        import
     }
 }
 
 impl ops::BitOr for TypeInfo {
     type Output = Self;
 
-    fn bitor(mut self, rhs: Self) -> Self {
-        self.import.extend(rhs.import);
-        Self {
-            name: format!("{} | {}", self.name, rhs.name),
-            import: self.import,
-        }
+    fn bitor(self, rhs: Self) -> Self {
+        Self::union([self, rhs])
     }
 }
 
@@ -306,6 +403,7 @@ pub trait PyStubType {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::generate::{Import, PythonVersion, RenderContext};
     use maplit::hashset;
     use std::collections::HashMap;
     use test_case::test_case;
@@ -322,12 +420,49 @@ mod test {
     #[test_case(HashMap::<u32, Vec<u32>>::type_output(), "builtins.dict[builtins.int, builtins.list[builtins.int]]", hashset! { "builtins".into() } ; "HashMap_u32_Vec_u32_output")]
     #[test_case(HashSet::<u32>::type_input(), "builtins.set[builtins.int]", hashset! { "builtins".into() } ; "HashSet_u32_input")]
     #[test_case(indexmap::IndexSet::<u32>::type_input(), "builtins.set[builtins.int]", hashset! { "builtins".into() } ; "IndexSet_u32_input")]
+    #[test_case(Option::<Option<u32>>::type_input(), "builtins.int | None", hashset! { "builtins".into() } ; "Option_Option_u32_input")]
+    #[test_case(TypeInfo::dict_of::<u32, String>(), "builtins.dict[builtins.int, builtins.str]", hashset! { "builtins".into() } ; "dict_of")]
+    #[test_case(TypeInfo::none() | u32::type_output() | String::type_output() | u32::type_output(), "builtins.int | builtins.str | None", hashset! { "builtins".into() } ; "union_normalized")]
+    #[test_case(Option::<u32>::type_output() | TypeInfo::none(), "builtins.int | None", hashset! { "builtins".into() } ; "union_optional")]
+    #[test_case(TypeInfo::literal(["r", "w\""]), r#"typing.Literal["r", "w\""]"#, hashset! { "typing".into() } ; "literal")]
+    #[test_case(TypeInfo::literal(["a\tb\r\n", "\u{0}\u{7f}\u{85}\u{2028}"]), r#"typing.Literal["a\tb\r\n", "\x00\x7f\x85\u2028"]"#, hashset! { "typing".into() } ; "literal_escape")]
+    #[test_case(TypeInfo::literal([1, -1]) | TypeInfo::literal([true]), "typing.Literal[1, -1] | typing.Literal[True]", hashset! { "typing".into() } ; "literal_int_bool")]
+    #[test_case(TypeInfo::callable(Some(vec![u32::type_input()]), TypeInfo::none()), "collections.abc.Callable[[builtins.int], None]", hashset! { "collections.abc".into(), "builtins".into() } ; "callable")]
+    #[test_case(TypeInfo::callable(None, TypeInfo::any()), "collections.abc.Callable[..., typing.Any]", hashset! { "collections.abc".into(), "typing".into() } ; "callable_any_args")]
+    #[test_case(::pyo3::types::PyBytes::type_output(), "builtins.bytes", hashset! { "builtins".into() } ; "PyBytes_output")]
     fn test(tinfo: TypeInfo, name: &str, import: HashSet<ImportRef>) {
-        assert_eq!(tinfo.name, name);
-        if import.is_empty() {
-            assert!(tinfo.import.is_empty());
-        } else {
-            assert_eq!(tinfo.import, import);
-        }
+        check(tinfo, name, import, PythonVersion::new(3, 10));
+    }
+
+    #[test_case(Option::<u32>::type_output(), "typing.Optional[builtins.int]", hashset! { "typing".into(), "builtins".into() } ; "optional")]
+    #[test_case(u32::type_output() | TypeInfo::none() | String::type_output(), "typing.Union[builtins.int, builtins.str, None]", hashset! { "typing".into(), "builtins".into() } ; "union")]
+    #[test_case(TypeInfo::from(TypeExpr::qualified("typing_extensions", "Self")), "typing_extensions.Self", hashset! { "typing_extensions".into() } ; "self_type")]
+    fn test_py39(tinfo: TypeInfo, name: &str, import: HashSet<ImportRef>) {
+        check(tinfo, name, import, PythonVersion::new(3, 9));
+    }
+
+    #[test]
+    fn test_py313() {
+        let self_type = TypeInfo::from(TypeExpr::qualified("typing_extensions", "Self"));
+        check(
+            self_type,
+            "typing.Self",
+            hashset! { "typing".into() },
+            PythonVersion::new(3, 13),
+        );
+    }
+
+    fn check(tinfo: TypeInfo, name: &str, import: HashSet<ImportRef>, target: PythonVersion) {
+        assert_eq!(RenderContext::new(target).display(&tinfo).to_string(), name);
+        // Names used as `module.name` are imported by `import module` in the default style
+        let modules: HashSet<ImportRef> = tinfo
+            .import(target)
+            .into_iter()
+            .map(|import_ref| match import_ref {
+                ImportRef::Qualified(type_ref) => ImportRef::Module(type_ref.module),
+                import_ref => import_ref,
+            })
+            .collect();
+        assert_eq!(modules, import);
     }
 }
@@ -3,20 +3,10 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 impl<T: PyStubType> PyStubType for Option<T> {
     fn type_input() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_input();
-        import.insert("typing".into());
-        TypeInfo {
-            name: format!("typing.Optional[{name}]"),
-            import,
-        }
+        TypeInfo::optional(T::type_input())
     }
     fn type_output() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_output();
-        import.insert("typing".into());
-        TypeInfo {
-            name: format!("typing.Optional[{name}]"),
-            import,
-        }
+        TypeInfo::optional(T::type_output())
     }
 }
 
@@ -40,12 +30,7 @@ impl<T: PyStubType, E> PyStubType for Result<T, E> {
 
 impl<T: PyStubType> PyStubType for Vec<T> {
     fn type_input() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_input();
-        import.insert("typing".into());
-        TypeInfo {
-            name: format!("typing.Sequence[{name}]"),
-            import,
-        }
+        TypeInfo::generic(TypeExpr::qualified("typing", "Sequence"), [T::type_input()])
     }
     fn type_output() -> TypeInfo {
         TypeInfo::list_of::<T>()
@@ -54,12 +39,7 @@ impl<T: PyStubType> PyStubType for Vec<T> {
 
 impl<T: PyStubType, const N: usize> PyStubType for [T; N] {
     fn type_input() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_input();
-        import.insert("typing".into());
-        TypeInfo {
-            name: format!("typing.Sequence[{name}]"),
-            import,
-        }
+        TypeInfo::generic(TypeExpr::qualified("typing", "Sequence"), [T::type_input()])
     }
     fn type_output() -> TypeInfo {
         TypeInfo::list_of::<T>()
@@ -87,36 +67,16 @@ impl<T: PyStubType> PyStubType for indexmap::IndexSet<T> {
 macro_rules! impl_map_inner {
     () => {
         fn type_input() -> TypeInfo {
-            let TypeInfo {
-                name: key_name,
-                mut import,
-            } = Key::type_input();
-            let TypeInfo {
-                name: value_name,
-                import: value_import,
-            } = Value::type_input();
-            import.extend(value_import);
-            import.insert("typing".into());
-            TypeInfo {
-                name: format!("typing.Mapping[{}, {}]", key_name, value_name),
-                import,
-            }
+            TypeInfo::generic(
+                TypeExpr::qualified("typing", "Mapping"),
+                [Key::type_input(), Value::type_input()],
+            )
         }
         fn type_output() -> TypeInfo {
-            let TypeInfo {
-                name: key_name,
-                mut import,
-            } = Key::type_output();
-            let TypeInfo {
-                name: value_name,
-                import: value_import,
-            } = Value::type_output();
-            import.extend(value_import);
-            import.insert("builtins".into());
-            TypeInfo {
-                name: format!("builtins.dict[{}, {}]", key_name, value_name),
-                import,
-            }
+            TypeInfo::generic(
+                TypeExpr::qualified("builtins", "dict"),
+                [Key::type_output(), Value::type_output()],
+            )
         }
     };
 }
@@ -139,30 +99,10 @@ macro_rules! impl_tuple {
     ($($T:ident),*) => {
         impl<$($T: PyStubType),*> PyStubType for ($($T),* ,) {
             fn type_output() -> TypeInfo {
-                let mut merged = HashSet::new();
-                let mut names = Vec::new();
-                $(
-                let TypeInfo { name, import } = $T::type_output();
-                names.push(name);
-                merged.extend(import);
-                )*
-                TypeInfo {
-                    name: format!("tuple[{}]", names.join(", ")),
-                    import: merged,
-                }
+                TypeInfo::generic(TypeExpr::Raw("tuple".to_string()), [$($T::type_output()),*])
             }
             fn type_input() -> TypeInfo {
-                let mut merged = HashSet::new();
-                let mut names = Vec::new();
-                $(
-                let TypeInfo { name, import } = $T::type_input();
-                names.push(name);
-                merged.extend(import);
-                )*
-                TypeInfo {
-                    name: format!("tuple[{}]", names.join(", ")),
-                    import: merged,
-                }
+                TypeInfo::generic(TypeExpr::Raw("tuple".to_string()), [$($T::type_input()),*])
             }
         }
     };
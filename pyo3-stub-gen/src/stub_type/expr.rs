@@ -0,0 +1,336 @@
+use super::{ImportRef, TypeRef};
+use crate::generate::{PythonVersion, Render, RenderContext};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
+
+/// Structured Python type expression, i.e. the name of [crate::TypeInfo].
+///
+/// The module names in the expression are kept apart from the type names,
+/// so that the stub file can refer to them either fully qualified like `typing.Sequence`,
+/// or by the short name `Sequence` imported by `from typing import Sequence`.
+/// See [crate::generate::TypeNameStyle].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TypeExpr {
+    /// A name of a module referred as `module.name`, e.g. `typing.Any` or `builtins.int`.
+    ///
+    /// The names backported by `typing_extensions`, e.g. `typing_extensions.Self`,
+    /// are taken from `typing` if the target Python version provides them.
+    Qualified(TypeRef),
+    /// A name imported by `from module import name`, e.g. a class defined in another submodule of the PyO3 module
+    Local(TypeRef),
+    /// `base[args, ...]`, e.g. `typing.Sequence[builtins.int]`
+    Generic {
+        base: Box<TypeExpr>,
+        args: Vec<TypeExpr>,
+    },
+    /// `A | B | ...`, built by [TypeExpr::union] so that the members are flat, unique, and `None` comes last.
+    ///
+    /// This is written as `typing.Optional[A]` or `typing.Union[A, B]` when targeting Python 3.9,
+    /// see [crate::generate::PythonVersion].
+    Union(Vec<TypeExpr>),
+    /// `typing.Literal[values, ...]`, e.g. `typing.Literal["fast", "accurate"]`
+    Literal(Vec<LiteralValue>),
+    /// `collections.abc.Callable[[args, ...], ret]`, or `collections.abc.Callable[..., ret]` if `args` is `None`
+    Callable {
+        args: Option<Vec<TypeExpr>>,
+        ret: Box<TypeExpr>,
+    },
+    /// `None`
+    None,
+    /// Type expression written as is, e.g. given by `#[gen_stub(override_type(type_repr = ...))]`.
+    ///
+    /// The modules used in it must be listed in [crate::TypeInfo::import].
+    /// Unlike the other variants, the names in it are not shortened or aliased by the imports of the module.
+    Raw(String),
+}
+
+/// A value of `typing.Literal`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LiteralValue {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+impl From<&str> for LiteralValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+
+impl From<String> for LiteralValue {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<i64> for LiteralValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<bool> for LiteralValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl fmt::Display for LiteralValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Str(value) => {
+                write!(f, "\"")?;
+                for c in value.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        // Other characters which cannot appear in a string literal as is, as Python's `repr`
+                        c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                            let code = u32::from(c);
+                            if code <= 0xff {
+                                write!(f, "\\x{code:02x}")?
+                            } else {
+                                write!(f, "\\u{code:04x}")?
+                            }
+                        }
+                        c => write!(f, "{c}")?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Bool(true) => write!(f, "True"),
+            Self::Bool(false) => write!(f, "False"),
+        }
+    }
+}
+
+impl TypeExpr {
+    /// `module.name`, e.g. `TypeExpr::qualified("typing", "Any")` for `typing.Any`
+    pub fn qualified(module: &str, name: &str) -> Self {
+        Self::Qualified(TypeRef::new(module.into(), name.to_string()))
+    }
+
+    /// Union of the members, normalized as follows:
+    ///
+    /// - Nested unions are flattened, e.g. `(A | B) | C` into `A | B | C`
+    /// - Duplicated members are removed, keeping the first one, e.g. `A | B | A` into `A | B`
+    /// - `None` is moved to the last
+    /// - A union of single member is the member itself
+    ///
+    /// ```
+    /// use pyo3_stub_gen::TypeExpr;
+    /// let a = TypeExpr::qualified("builtins", "int");
+    /// let optional = TypeExpr::union([TypeExpr::None, a.clone()]);
+    /// assert_eq!(
+    ///     TypeExpr::union([optional, a.clone()]),
+    ///     TypeExpr::Union(vec![a, TypeExpr::None])
+    /// );
+    /// ```
+    pub fn union(members: impl IntoIterator<Item = TypeExpr>) -> Self {
+        let mut flat = Vec::new();
+        let mut has_none = false;
+        for member in members {
+            let inner = match member {
+                Self::Union(inner) => inner,
+                member => vec![member],
+            };
+            for member in inner {
+                if member == Self::None {
+                    has_none = true;
+                } else if !flat.contains(&member) {
+                    flat.push(member);
+                }
+            }
+        }
+        if has_none {
+            flat.push(Self::None);
+        }
+        if flat.len() == 1 {
+            flat.pop().unwrap()
+        } else {
+            Self::Union(flat)
+        }
+    }
+
+    /// How a union of `members` is written for the target Python version,
+    /// i.e. `A | B` if `None`, or `typing.Optional` and `typing.Union`
+    fn union_form(members: &[TypeExpr], target: PythonVersion) -> Option<&'static str> {
+        if target.has_union_operator() {
+            None
+        } else if let [_, Self::None] = members {
+            Some("Optional")
+        } else {
+            Some("Union")
+        }
+    }
+
+    /// Imports required by the names in this expression for the target Python version
+    pub fn import(&self, target: PythonVersion) -> HashSet<ImportRef> {
+        let mut import = HashSet::new();
+        self.collect_import(&mut import, target);
+        import
+    }
+
+    fn collect_import(&self, import: &mut HashSet<ImportRef>, target: PythonVersion) {
+        match self {
+            Self::Qualified(type_ref) => {
+                import.insert(ImportRef::Qualified(resolve_typing(type_ref, target)));
+            }
+            Self::Local(type_ref) => {
+                import.insert(ImportRef::Type(type_ref.clone()));
+            }
+            Self::Generic { base, args } => {
+                base.collect_import(import, target);
+                for arg in args {
+                    arg.collect_import(import, target);
+                }
+            }
+            Self::Union(members) => {
+                if let Some(form) = Self::union_form(members, target) {
+                    import.insert(ImportRef::Qualified(TypeRef::new(
+                        "typing".into(),
+                        form.to_string(),
+                    )));
+                }
+                for member in members {
+                    member.collect_import(import, target);
+                }
+            }
+            Self::Literal(_) => {
+                import.insert(ImportRef::Qualified(TypeRef::new(
+                    "typing".into(),
+                    "Literal".to_string(),
+                )));
+            }
+            Self::Callable { args, ret } => {
+                import.insert(ImportRef::Qualified(TypeRef::new(
+                    "collections.abc".into(),
+                    "Callable".to_string(),
+                )));
+                for arg in args.iter().flatten() {
+                    arg.collect_import(import, target);
+                }
+                ret.collect_import(import, target);
+            }
+            Self::None | Self::Raw(_) => {}
+        }
+    }
+}
+
+/// `typing.Self` or `typing_extensions.Self` depending on the target Python version
+fn resolve_typing(type_ref: &TypeRef, target: PythonVersion) -> TypeRef {
+    // `types.EllipsisType` is added in Python 3.10, and typeshed provides `builtins.ellipsis` for the older versions
+    if type_ref.module.get() == Some("types")
+        && type_ref.name == "EllipsisType"
+        && target < PythonVersion::new(3, 10)
+    {
+        return TypeRef::new("builtins".into(), "ellipsis".to_string());
+    }
+    if let Some("typing" | "typing_extensions") = type_ref.module.get() {
+        if let Some(module) = target.typing_module(&type_ref.name) {
+            return TypeRef::new(module.into(), type_ref.name.clone());
+        }
+    }
+    type_ref.clone()
+}
+
+/// Write `items` separated by `, `
+fn write_list(f: &mut fmt::Formatter, items: &[impl fmt::Display]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{item}")?;
+    }
+    Ok(())
+}
+
+/// Write `exprs` rendered in `ctx` separated by `, `
+fn write_exprs(f: &mut fmt::Formatter, exprs: &[TypeExpr], ctx: &RenderContext) -> fmt::Result {
+    for (i, expr) in exprs.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        expr.render(f, ctx)?;
+    }
+    Ok(())
+}
+
+impl From<&str> for TypeExpr {
+    fn from(expr: &str) -> Self {
+        Self::Raw(expr.to_string())
+    }
+}
+
+impl From<String> for TypeExpr {
+    fn from(expr: String) -> Self {
+        Self::Raw(expr)
+    }
+}
+
+impl Render for TypeExpr {
+    fn render(&self, f: &mut fmt::Formatter, ctx: &RenderContext) -> fmt::Result {
+        match self {
+            Self::Qualified(type_ref) => {
+                let TypeRef { module, name } = resolve_typing(type_ref, ctx.target);
+                write!(f, "{}", ctx.qualified_name(&module, &name))
+            }
+            Self::Local(type_ref) => write!(f, "{}", type_ref.name),
+            Self::Generic { base, args } => {
+                base.render(f, ctx)?;
+                write!(f, "[")?;
+                write_exprs(f, args, ctx)?;
+                write!(f, "]")
+            }
+            Self::Union(members) => {
+                if let Some(form) = Self::union_form(members, ctx.target) {
+                    write!(f, "{}[", ctx.qualified_name(&"typing".into(), form))?;
+                    match form {
+                        "Optional" => write_exprs(f, &members[..1], ctx)?,
+                        _ => write_exprs(f, members, ctx)?,
+                    }
+                    return write!(f, "]");
+                }
+                for (i, member) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    member.render(f, ctx)?;
+                }
+                Ok(())
+            }
+            Self::Literal(values) => {
+                let literal = ctx.qualified_name(&"typing".into(), "Literal");
+                write!(f, "{literal}[")?;
+                write_list(f, values)?;
+                write!(f, "]")
+            }
+            Self::Callable { args, ret } => {
+                let callable = ctx.qualified_name(&"collections.abc".into(), "Callable");
+                write!(f, "{callable}[")?;
+                match args {
+                    Some(args) => {
+                        write!(f, "[")?;
+                        write_exprs(f, args, ctx)?;
+                        write!(f, "]")?;
+                    }
+                    None => write!(f, "...")?,
+                }
+                write!(f, ", {}]", ctx.display(ret.as_ref()))
+            }
+            Self::None => write!(f, "None"),
+            Self::Raw(expr) => write!(f, "{expr}"),
+        }
+    }
+}
+
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &RenderContext::default())
+    }
+}
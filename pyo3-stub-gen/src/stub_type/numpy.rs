@@ -1,5 +1,4 @@
-use super::{PyStubType, TypeInfo};
-use maplit::hashset;
+use super::{PyStubType, TypeExpr, TypeInfo};
 use numpy::{
     ndarray::Dimension, Element, PyArray, PyArrayDescr, PyReadonlyArray, PyReadwriteArray,
     PyUntypedArray,
@@ -13,10 +12,7 @@ macro_rules! impl_numpy_scalar {
     ($ty:ty, $name:expr) => {
         impl NumPyScalar for $ty {
             fn type_() -> TypeInfo {
-                TypeInfo {
-                    name: format!("numpy.{}", $name),
-                    import: hashset!["numpy".into()],
-                }
+                TypeExpr::qualified("numpy", $name).into()
             }
         }
     };
@@ -37,21 +33,16 @@ impl_numpy_scalar!(num_complex::Complex64, "complex128");
 
 impl<T: NumPyScalar, D> PyStubType for PyArray<T, D> {
     fn type_output() -> TypeInfo {
-        let TypeInfo { name, mut import } = T::type_();
-        import.insert("numpy.typing".into());
-        TypeInfo {
-            name: format!("numpy.typing.NDArray[{name}]"),
-            import,
-        }
+        TypeInfo::generic(TypeExpr::qualified("numpy.typing", "NDArray"), [T::type_()])
     }
 }
 
 impl PyStubType for PyUntypedArray {
     fn type_output() -> TypeInfo {
-        TypeInfo {
-            name: "numpy.typing.NDArray[typing.Any]".into(),
-            import: hashset!["numpy.typing".into(), "typing".into()],
-        }
+        TypeInfo::generic(
+            TypeExpr::qualified("numpy.typing", "NDArray"),
+            [TypeInfo::any()],
+        )
     }
 }
 
@@ -77,9 +68,6 @@ where
 
 impl PyStubType for PyArrayDescr {
     fn type_output() -> TypeInfo {
-        TypeInfo {
-            name: "numpy.dtype".into(),
-            import: hashset!["numpy".into()],
-        }
+        TypeExpr::qualified("numpy", "dtype").into()
     }
 }
@@ -6,14 +6,10 @@ use ::pyo3::{
     types::*,
     Bound, Py, PyClass, PyRef, PyRefMut,
 };
-use maplit::hashset;
 
 impl PyStubType for PyAny {
     fn type_output() -> TypeInfo {
-        TypeInfo {
-            name: "typing.Any".to_string(),
-            import: hashset! { "typing".into() },
-        }
+        TypeInfo::any()
     }
 }
 
@@ -57,10 +53,7 @@ macro_rules! impl_builtin {
     ($ty:ty, $pytype:expr) => {
         impl PyStubType for $ty {
             fn type_output() -> TypeInfo {
-                TypeInfo {
-                    name: $pytype.to_string(),
-                    import: HashSet::new(),
-                }
+                TypeInfo::builtin($pytype)
             }
         }
     };
@@ -80,16 +73,17 @@ impl_builtin!(PyByteArray, "bytearray");
 impl_builtin!(PyBytes, "bytes");
 impl_builtin!(PyBackedBytes, "bytes");
 impl_builtin!(PyType, "type");
+impl_builtin!(PyBool, "bool");
+impl_builtin!(PyFrozenSet, "frozenset");
+impl_builtin!(PyMemoryView, "memoryview");
+impl_builtin!(PyRange, "range");
 impl_builtin!(CompareOp, "int");
 
 macro_rules! impl_simple {
     ($ty:ty, $mod:expr, $pytype:expr) => {
         impl PyStubType for $ty {
             fn type_output() -> TypeInfo {
-                TypeInfo {
-                    name: concat!($mod, ".", $pytype).to_string(),
-                    import: hashset! { $mod.into() },
-                }
+                TypeExpr::qualified($mod, $pytype).into()
             }
         }
     };
@@ -100,3 +94,76 @@ impl_simple!(PyDateTime, "datetime", "datetime");
 impl_simple!(PyDelta, "datetime", "timedelta");
 impl_simple!(PyTime, "datetime", "time");
 impl_simple!(PyTzInfo, "datetime", "tzinfo");
+impl_simple!(PyIterator, "collections.abc", "Iterator");
+impl_simple!(PySequence, "collections.abc", "Sequence");
+impl_simple!(PyMapping, "collections.abc", "Mapping");
+impl_simple!(PyMappingProxy, "types", "MappingProxyType");
+impl_simple!(PyCFunction, "types", "BuiltinFunctionType");
+impl_simple!(PyModule, "types", "ModuleType");
+impl_simple!(PyCode, "types", "CodeType");
+impl_simple!(PyTraceback, "types", "TracebackType");
+// `builtins.ellipsis` before Python 3.10, see `TypeExpr::Qualified`
+impl_simple!(PyEllipsis, "types", "EllipsisType");
+// `types.CapsuleType` since Python 3.13
+impl_simple!(PyCapsule, "typing_extensions", "CapsuleType");
+impl_simple!(PyWeakrefReference, "weakref", "ReferenceType");
+// `PyFunction` and `PySuper` are not implemented since PyO3 provides them only for some interpreters and ABIs,
+// which is not known here without the cfgs of PyO3's build script
+
+impl PyStubType for PyNone {
+    fn type_output() -> TypeInfo {
+        TypeInfo::none()
+    }
+}
+
+impl PyStubType for PyWeakrefProxy {
+    fn type_output() -> TypeInfo {
+        TypeInfo::union([
+            TypeExpr::qualified("weakref", "ProxyType").into(),
+            TypeExpr::qualified("weakref", "CallableProxyType").into(),
+        ])
+    }
+}
+
+impl PyStubType for PyWeakref {
+    fn type_output() -> TypeInfo {
+        PyWeakrefReference::type_output() | PyWeakrefProxy::type_output()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generate::{PythonVersion, RenderContext};
+    use test_case::test_case;
+
+    #[test_case(PyBool::type_output(), "builtins.bool" ; "PyBool")]
+    #[test_case(PyNone::type_output(), "None" ; "PyNone")]
+    #[test_case(PyFrozenSet::type_output(), "builtins.frozenset" ; "PyFrozenSet")]
+    #[test_case(PyMemoryView::type_output(), "builtins.memoryview" ; "PyMemoryView")]
+    #[test_case(PyRange::type_output(), "builtins.range" ; "PyRange")]
+    #[test_case(PyIterator::type_output(), "collections.abc.Iterator" ; "PyIterator")]
+    #[test_case(PySequence::type_output(), "collections.abc.Sequence" ; "PySequence")]
+    #[test_case(PyMapping::type_output(), "collections.abc.Mapping" ; "PyMapping")]
+    #[test_case(PyMappingProxy::type_output(), "types.MappingProxyType" ; "PyMappingProxy")]
+    #[test_case(PyCFunction::type_output(), "types.BuiltinFunctionType" ; "PyCFunction")]
+    #[test_case(PyModule::type_output(), "types.ModuleType" ; "PyModule")]
+    #[test_case(PyCode::type_output(), "types.CodeType" ; "PyCode")]
+    #[test_case(PyTraceback::type_output(), "types.TracebackType" ; "PyTraceback")]
+    #[test_case(PyWeakrefReference::type_output(), "weakref.ReferenceType" ; "PyWeakrefReference")]
+    #[test_case(PyWeakrefProxy::type_output(), "weakref.ProxyType | weakref.CallableProxyType" ; "PyWeakrefProxy")]
+    #[test_case(PyWeakref::type_output(), "weakref.ReferenceType | weakref.ProxyType | weakref.CallableProxyType" ; "PyWeakref")]
+    fn test(tinfo: TypeInfo, name: &str) {
+        let ctx = RenderContext::new(PythonVersion::new(3, 10));
+        assert_eq!(ctx.display(&tinfo).to_string(), name);
+    }
+
+    #[test_case(PyEllipsis::type_output(), "builtins.ellipsis", "types.EllipsisType" ; "PyEllipsis")]
+    #[test_case(PyCapsule::type_output(), "typing_extensions.CapsuleType", "types.CapsuleType" ; "PyCapsule")]
+    fn test_versions(tinfo: TypeInfo, py39: &str, py313: &str) {
+        let ctx = RenderContext::new(PythonVersion::new(3, 9));
+        assert_eq!(ctx.display(&tinfo).to_string(), py39);
+        let ctx = RenderContext::new(PythonVersion::new(3, 13));
+        assert_eq!(ctx.display(&tinfo).to_string(), py313);
+    }
+}
@@ -22,6 +22,7 @@
 //!
 
 use crate::{PyStubType, TypeInfo};
+use serde::{Deserialize, Serialize};
 use std::any::TypeId;
 
 /// Represents the target of type ignore comments
@@ -81,7 +82,7 @@ impl PartialEq for SignatureArg {
 }
 
 /// Type of a method
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum MethodType {
     Instance,
     Static,
@@ -117,6 +118,8 @@ pub struct MemberInfo {
 pub struct PyMethodsInfo {
     // The Rust struct type-id of `impl` block where `#[pymethod]` acts on
     pub struct_id: fn() -> TypeId,
+    /// The Rust type name of `impl` block, i.e. [std::any::type_name], for error reporting
+    pub struct_name: fn() -> &'static str,
     /// Method/Const with `#[classattr]`
     pub attrs: &'static [MemberInfo],
     /// Methods decorated with `#[getter]`
@@ -125,6 +128,9 @@ pub struct PyMethodsInfo {
     pub setters: &'static [MemberInfo],
     /// Other usual methods
     pub methods: &'static [MethodInfo],
+    /// Where this is submitted, i.e. [std::panic::Location::caller].
+    /// The methods of a class are ordered by this since the order of [inventory::iter] depends on the link order.
+    pub location: &'static std::panic::Location<'static>,
 }
 
 inventory::collect!(PyMethodsInfo);
@@ -146,6 +152,9 @@ pub struct PyClassInfo {
     pub setters: &'static [MemberInfo],
     /// Base classes specified by `#[pyclass(extends = Type)]`
     pub bases: &'static [fn() -> TypeInfo],
+    /// Type parameters specified by `#[gen_stub(generic = (T, ...))]`,
+    /// which are declared in the module of the class by `module_typevar!` or `module_paramspec!`
+    pub type_params: &'static [&'static str],
     /// Whether the class has eq attribute
     pub has_eq: bool,
     /// Whether the class has ord attribute
@@ -221,6 +230,9 @@ pub struct PyFunctionInfo {
     pub is_async: bool,
     pub deprecated: Option<DeprecatedInfo>,
     pub type_ignored: Option<IgnoreTarget>,
+    /// Where this is submitted, i.e. [std::panic::Location::caller].
+    /// The overloads are ordered by this since the order of [inventory::iter] depends on the link order.
+    pub location: &'static std::panic::Location<'static>,
 }
 
 inventory::collect!(PyFunctionInfo);
@@ -235,6 +247,84 @@ pub struct PyVariableInfo {
 
 inventory::collect!(PyVariableInfo);
 
+/// Info of a Rust struct with `#[gen_stub_typeddict]`, converted from a Python dict by `#[derive(FromPyObject)]`
+#[derive(Debug)]
+pub struct PyTypedDictInfo {
+    pub name: &'static str,
+    pub module: Option<&'static str>,
+    pub doc: &'static str,
+    /// `total` of `typing.TypedDict`, i.e. whether the keys are required by default
+    pub total: bool,
+    pub fields: &'static [TypedDictFieldInfo],
+}
+
+inventory::collect!(PyTypedDictInfo);
+
+/// A key of [PyTypedDictInfo]
+#[derive(Debug)]
+pub struct TypedDictFieldInfo {
+    pub name: &'static str,
+    pub r#type: fn() -> TypeInfo,
+    /// `false` if the key may be missing, i.e. for `#[pyo3(default)]` fields
+    pub required: bool,
+    pub doc: &'static str,
+}
+
+/// Info of a Rust trait with `#[gen_stub_protocol]`, generated as `typing.Protocol`
+#[derive(Debug)]
+pub struct PyProtocolInfo {
+    pub name: &'static str,
+    pub module: Option<&'static str>,
+    pub doc: &'static str,
+    /// Methods decorated with `#[getter]`
+    pub getters: &'static [MemberInfo],
+    /// Other usual methods
+    pub methods: &'static [MethodInfo],
+}
+
+inventory::collect!(PyProtocolInfo);
+
+/// Type alias `name = r#type`, e.g. the union of the variants of a `#[gen_stub_union(alias)]` enum
+#[derive(Debug)]
+pub struct PyTypeAliasInfo {
+    pub name: &'static str,
+    pub module: Option<&'static str>,
+    pub doc: &'static str,
+    pub r#type: fn() -> TypeInfo,
+}
+
+inventory::collect!(PyTypeAliasInfo);
+
+/// Kind of a type variable declared at the module level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TypeVarKind {
+    /// `T = typing.TypeVar("T")`
+    TypeVar,
+    /// `P = typing.ParamSpec("P")`
+    ParamSpec,
+}
+
+/// Module-level type variable declared by [module_typevar!](crate::module_typevar) or [module_paramspec!](crate::module_paramspec)
+#[derive(Debug)]
+pub struct PyTypeVarInfo {
+    pub name: &'static str,
+    pub module: &'static str,
+    pub kind: TypeVarKind,
+    pub bound: Option<fn() -> TypeInfo>,
+    pub constraints: &'static [fn() -> TypeInfo],
+}
+
+inventory::collect!(PyTypeVarInfo);
+
+/// Class, enum, or function with `#[gen_stub(private)]`, which is excluded from `__all__`
+#[derive(Debug)]
+pub struct PrivateInfo {
+    pub name: &'static str,
+    pub module: Option<&'static str>,
+}
+
+inventory::collect!(PrivateInfo);
+
 #[derive(Debug)]
 pub struct ModuleDocInfo {
     pub module: &'static str,
@@ -0,0 +1,280 @@
+//! Wrappers of Python objects which behave as [Bound] in Rust,
+//! but carry a more precise Python type for the stub file.
+
+use crate::{PyStubType, TypeExpr, TypeInfo};
+use pyo3::{
+    prelude::*,
+    types::{PyDict, PyIterator, PyList, PySequence},
+};
+use std::{convert::Infallible, fmt, marker::PhantomData, ops::Deref};
+
+/// Define a wrapper of `Bound<'py, $native>` with marker type parameters,
+/// which is extracted, converted, and dereferenced as the underlying [Bound].
+macro_rules! typed_bound {
+    ($(#[$meta:meta])* $name:ident<$($param:ident),+>: $native:ty) => {
+        $(#[$meta])*
+        pub struct $name<'py, $($param: ?Sized),+> {
+            inner: Bound<'py, $native>,
+            _type: PhantomData<($(PhantomData<$param>,)+)>,
+        }
+
+        impl<'py, $($param: ?Sized),+> $name<'py, $($param),+> {
+            pub fn into_inner(self) -> Bound<'py, $native> {
+                self.inner
+            }
+        }
+
+        impl<'py, $($param: ?Sized),+> From<Bound<'py, $native>> for $name<'py, $($param),+> {
+            fn from(inner: Bound<'py, $native>) -> Self {
+                Self {
+                    inner,
+                    _type: PhantomData,
+                }
+            }
+        }
+
+        impl<'py, $($param: ?Sized),+> Deref for $name<'py, $($param),+> {
+            type Target = Bound<'py, $native>;
+            fn deref(&self) -> &Self::Target {
+                &self.inner
+            }
+        }
+
+        impl<$($param: ?Sized),+> Clone for $name<'_, $($param),+> {
+            fn clone(&self) -> Self {
+                self.inner.clone().into()
+            }
+        }
+
+        impl<$($param: ?Sized),+> fmt::Debug for $name<'_, $($param),+> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.inner.fmt(f)
+            }
+        }
+
+        impl<'py, $($param: ?Sized),+> FromPyObject<'py> for $name<'py, $($param),+> {
+            fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+                Ok(ob.downcast::<$native>()?.clone().into())
+            }
+        }
+
+        impl<'py, $($param: ?Sized),+> IntoPyObject<'py> for $name<'py, $($param),+> {
+            type Target = $native;
+            type Output = Bound<'py, $native>;
+            type Error = Infallible;
+            fn into_pyobject(self, _py: Python<'py>) -> Result<Self::Output, Self::Error> {
+                Ok(self.inner)
+            }
+        }
+    };
+}
+
+typed_bound! {
+    /// Python object expected to implement the protocol `P`, usually `dyn Trait` of `#[gen_stub_protocol]`.
+    ///
+    /// As duck typing in Python, the object is not checked on extraction,
+    /// and methods are called through [Deref] to [Bound<'py, PyAny>].
+    ///
+    /// ```
+    /// use pyo3::prelude::*;
+    /// use pyo3_stub_gen::{derive::*, typed::PyProtocol};
+    ///
+    /// #[gen_stub_protocol]
+    /// pub trait Reader {
+    ///     fn read(&self, n: usize) -> Vec<u8>;
+    /// }
+    ///
+    /// #[gen_stub_pyfunction]
+    /// #[pyfunction]
+    /// fn read_header(reader: PyProtocol<'_, dyn Reader>) -> PyResult<Vec<u8>> {
+    ///     reader.call_method1("read", (4,))?.extract()
+    /// }
+    /// ```
+    PyProtocol<P>: PyAny
+}
+
+impl<P: PyStubType + ?Sized> PyStubType for PyProtocol<'_, P> {
+    fn type_input() -> TypeInfo {
+        P::type_input()
+    }
+    fn type_output() -> TypeInfo {
+        P::type_output()
+    }
+}
+
+typed_bound! {
+    /// Python callable taking the tuple `Args` of arguments and returning `R`,
+    /// i.e. `collections.abc.Callable[[A, B], R]` for `PyCallable<'py, (A, B), R>`.
+    ///
+    /// The object is not checked on extraction as the signature is unknown at runtime,
+    /// and it is called through [Deref] to [Bound<'py, PyAny>].
+    ///
+    /// ```
+    /// use pyo3::prelude::*;
+    /// use pyo3_stub_gen::{derive::*, typed::PyCallable};
+    ///
+    /// #[gen_stub_pyfunction]
+    /// #[pyfunction]
+    /// fn apply(f: PyCallable<'_, (i64, String), bool>) -> PyResult<bool> {
+    ///     f.call1((1, "one"))?.extract()
+    /// }
+    /// ```
+    PyCallable<Args, R>: PyAny
+}
+
+/// Types of the positional arguments of [PyCallable], implemented for tuples
+pub trait PyCallableArgs {
+    /// Types of the arguments passed from the caller
+    fn type_input() -> Vec<TypeInfo>;
+    /// Types of the arguments received by the callee
+    fn type_output() -> Vec<TypeInfo>;
+}
+
+macro_rules! impl_callable_args {
+    ($($T:ident),*) => {
+        impl<$($T: PyStubType),*> PyCallableArgs for ($($T,)*) {
+            fn type_input() -> Vec<TypeInfo> {
+                vec![$($T::type_input()),*]
+            }
+            fn type_output() -> Vec<TypeInfo> {
+                vec![$($T::type_output()),*]
+            }
+        }
+    };
+}
+
+impl_callable_args!();
+impl_callable_args!(T1);
+impl_callable_args!(T1, T2);
+impl_callable_args!(T1, T2, T3);
+impl_callable_args!(T1, T2, T3, T4);
+impl_callable_args!(T1, T2, T3, T4, T5);
+impl_callable_args!(T1, T2, T3, T4, T5, T6);
+impl_callable_args!(T1, T2, T3, T4, T5, T6, T7);
+impl_callable_args!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_callable_args!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+
+impl<Args: PyCallableArgs + ?Sized, R: PyStubType + ?Sized> PyStubType for PyCallable<'_, Args, R> {
+    // A callable passed from Python is called in Rust,
+    // i.e. Rust values are passed as the arguments and the return value is extracted.
+    fn type_input() -> TypeInfo {
+        TypeInfo::callable(Some(Args::type_output()), R::type_input())
+    }
+    fn type_output() -> TypeInfo {
+        TypeInfo::callable(Some(Args::type_input()), R::type_output())
+    }
+}
+
+typed_bound! {
+    /// `list` whose elements are `T`, i.e. `builtins.list[T]`.
+    ///
+    /// Only the `list` type is checked on extraction as [Bound<'py, PyList>], and the elements are not.
+    PyListOf<T>: PyList
+}
+
+// `list` and `dict` are invariant, so the same element types are used for both input and output,
+// i.e. `type_input` is not overridden unlike the covariant `Sequence` and `Iterator`.
+impl<T: PyStubType + ?Sized> PyStubType for PyListOf<'_, T> {
+    fn type_output() -> TypeInfo {
+        TypeInfo::generic(TypeExpr::qualified("builtins", "list"), [T::type_output()])
+    }
+}
+
+typed_bound! {
+    /// `dict` whose keys are `K` and values are `V`, i.e. `builtins.dict[K, V]`.
+    ///
+    /// Only the `dict` type is checked on extraction as [Bound<'py, PyDict>], and the items are not.
+    PyDictOf<K, V>: PyDict
+}
+
+impl<K: PyStubType + ?Sized, V: PyStubType + ?Sized> PyStubType for PyDictOf<'_, K, V> {
+    fn type_output() -> TypeInfo {
+        TypeInfo::generic(
+            TypeExpr::qualified("builtins", "dict"),
+            [K::type_output(), V::type_output()],
+        )
+    }
+}
+
+typed_bound! {
+    /// Sequence whose elements are `T`, i.e. `collections.abc.Sequence[T]`.
+    ///
+    /// Extracted as [Bound<'py, PySequence>], and the elements are not checked.
+    PySequenceOf<T>: PySequence
+}
+
+impl<T: PyStubType + ?Sized> PyStubType for PySequenceOf<'_, T> {
+    fn type_input() -> TypeInfo {
+        TypeInfo::generic(
+            TypeExpr::qualified("collections.abc", "Sequence"),
+            [T::type_input()],
+        )
+    }
+    fn type_output() -> TypeInfo {
+        TypeInfo::generic(
+            TypeExpr::qualified("collections.abc", "Sequence"),
+            [T::type_output()],
+        )
+    }
+}
+
+typed_bound! {
+    /// Iterator yielding `T`, i.e. `collections.abc.Iterator[T]`.
+    ///
+    /// Extracted as [Bound<'py, PyIterator>], i.e. an iterable which is not an iterator is rejected,
+    /// and the items are not checked.
+    PyIteratorOf<T>: PyIterator
+}
+
+impl<T: PyStubType + ?Sized> PyStubType for PyIteratorOf<'_, T> {
+    fn type_input() -> TypeInfo {
+        TypeInfo::generic(
+            TypeExpr::qualified("collections.abc", "Iterator"),
+            [T::type_input()],
+        )
+    }
+    fn type_output() -> TypeInfo {
+        TypeInfo::generic(
+            TypeExpr::qualified("collections.abc", "Iterator"),
+            [T::type_output()],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_callable() {
+        type Callback<'py> = PyCallable<'py, (String, f64), bool>;
+        assert_eq!(
+            Callback::type_input().to_string(),
+            "collections.abc.Callable[[builtins.str, builtins.float], builtins.bool]"
+        );
+        assert_eq!(
+            PyCallable::<(), ()>::type_output().to_string(),
+            "collections.abc.Callable[[], None]"
+        );
+    }
+
+    #[test]
+    fn test_containers() {
+        assert_eq!(
+            PyListOf::<u32>::type_output().to_string(),
+            "builtins.list[builtins.int]"
+        );
+        assert_eq!(
+            PyDictOf::<String, Vec<u32>>::type_input().to_string(),
+            "builtins.dict[builtins.str, builtins.list[builtins.int]]"
+        );
+        assert_eq!(
+            PySequenceOf::<f64>::type_input().to_string(),
+            "collections.abc.Sequence[builtins.float]"
+        );
+        assert_eq!(
+            PyIteratorOf::<PyListOf<String>>::type_output().to_string(),
+            "collections.abc.Iterator[builtins.list[builtins.str]]"
+        );
+    }
+}